@@ -0,0 +1,167 @@
+use crate::common::{pack_datacenter, BackoffStrategy, ClockSource, DefaultSnowflake, Result, SnowflakeError, SystemClock};
+use crate::multi_thread::sync_generator;
+use crate::raw::FieldOrder;
+#[cfg(feature = "tokio")]
+use crate::multi_thread::async_generator;
+use crate::single_thread::sync_generator as single_thread_generator;
+
+/// A builder for the Snowflake ID generators.
+///
+/// The epoch and worker ID default to `0`. Ranges are validated when building.
+#[derive(Debug, Clone)]
+pub struct SnowflakeBuilder<C = ()> {
+    epoch: i64,
+    worker_id: u16,
+    datacenter_id: Option<u8>,
+    sequence_stride: Option<(u16, u16)>,
+    field_order: FieldOrder,
+    initial_sequence_seed: Option<u64>,
+    backoff: BackoffStrategy,
+    clock: C,
+}
+
+impl SnowflakeBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            epoch: 0,
+            worker_id: 0,
+            datacenter_id: None,
+            sequence_stride: None,
+            field_order: FieldOrder::default(),
+            initial_sequence_seed: None,
+            backoff: BackoffStrategy::default(),
+            clock: (),
+        }
+    }
+
+    /// Build a multi-threaded synchronous generator using the system clock.
+    pub fn build_sync(self) -> Result<sync_generator::SnowflakeGenerator> {
+        self.clock(SystemClock::new()?).build_sync()
+    }
+
+    /// Build a multi-threaded asynchronous generator using the system clock.
+    #[cfg(feature = "tokio")]
+    pub fn build_async(self) -> Result<async_generator::SnowflakeGenerator> {
+        self.clock(SystemClock::new()?).build_async()
+    }
+
+    /// Build a single-threaded synchronous generator using the system clock.
+    pub fn build_single_thread(self) -> Result<single_thread_generator::SnowflakeGenerator> {
+        self.clock(SystemClock::new()?).build_single_thread()
+    }
+}
+
+impl Default for SnowflakeBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> SnowflakeBuilder<C> {
+    /// The custom epoch timestamp in milliseconds.
+    pub fn epoch(mut self, epoch: i64) -> Self {
+        self.epoch = epoch;
+        self
+    }
+
+    /// The worker ID (0-1023, or 0-31 when a datacenter ID is set).
+    pub fn worker_id(mut self, worker_id: u16) -> Self {
+        self.worker_id = worker_id;
+        self
+    }
+
+    /// The datacenter ID (0-31), enabling the Twitter-style datacenter + worker split.
+    pub fn datacenter_id(mut self, datacenter_id: u8) -> Self {
+        self.datacenter_id = Some(datacenter_id);
+        self
+    }
+
+    /// Hand out only every `step`-th sequence number of each millisecond, starting at `offset`,
+    /// e.g. to give each database shard a disjoint slice of the IDs of one worker.
+    pub fn sequence_stride(mut self, step: u16, offset: u16) -> Self {
+        self.sequence_stride = Some((step, offset));
+        self
+    }
+
+    /// The order of the worker ID and sequence fields. See [`FieldOrder`].
+    pub fn field_order(mut self, order: FieldOrder) -> Self {
+        self.field_order = order;
+        self
+    }
+
+    /// Start the first millisecond at a sequence number derived from `seed`, e.g. a hash of
+    /// the node name. See [`RawSnowflake::randomize_initial_sequence`](crate::raw::RawSnowflake::randomize_initial_sequence).
+    pub fn randomize_initial_sequence(mut self, seed: u64) -> Self {
+        self.initial_sequence_seed = Some(seed);
+        self
+    }
+
+    /// What the generator does when it has to wait for the clock, e.g. `BackoffStrategy::Error`
+    /// for an interactive path that must not block. Defaults to `BackoffStrategy::SleepMs`.
+    pub fn backoff_strategy(mut self, backoff: BackoffStrategy) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// The clock used to read the current time.
+    pub fn clock<D: ClockSource>(self, clock: D) -> SnowflakeBuilder<D> {
+        SnowflakeBuilder {
+            epoch: self.epoch,
+            worker_id: self.worker_id,
+            datacenter_id: self.datacenter_id,
+            sequence_stride: self.sequence_stride,
+            field_order: self.field_order,
+            initial_sequence_seed: self.initial_sequence_seed,
+            backoff: self.backoff,
+            clock,
+        }
+    }
+
+    fn combined_worker_id(&self) -> Result<u16> {
+        match self.datacenter_id {
+            Some(_) if self.worker_id > 0x1F => Err(SnowflakeError::WorkerIdOutOfRange),
+            Some(datacenter_id) => pack_datacenter(datacenter_id, self.worker_id as u8),
+            None => Ok(self.worker_id),
+        }
+    }
+}
+
+impl<C: ClockSource> SnowflakeBuilder<C> {
+    fn build_state(self) -> Result<DefaultSnowflake<C>> {
+        let worker_id = self.combined_worker_id()?;
+        let (step, offset) = self.sequence_stride.unwrap_or((1, 0));
+        let mut state =
+            DefaultSnowflake::with_clock_and_stride(self.epoch, worker_id, self.clock, step, offset)?.field_order(self.field_order);
+        if let Some(seed) = self.initial_sequence_seed {
+            state.randomize_initial_sequence(seed);
+        }
+        state.set_backoff_strategy(self.backoff);
+        Ok(state)
+    }
+
+    /// Build a multi-threaded synchronous generator.
+    ///
+    /// # Errors
+    /// Returns the same errors as the generator constructors.
+    pub fn build_sync(self) -> Result<sync_generator::SnowflakeGenerator<C>> {
+        Ok(sync_generator::SnowflakeGenerator::from_state(self.build_state()?))
+    }
+
+    /// Build a multi-threaded asynchronous generator.
+    ///
+    /// # Errors
+    /// Returns the same errors as the generator constructors.
+    #[cfg(feature = "tokio")]
+    pub fn build_async(self) -> Result<async_generator::SnowflakeGenerator<C>> {
+        Ok(async_generator::SnowflakeGenerator::from_state(self.build_state()?))
+    }
+
+    /// Build a single-threaded synchronous generator.
+    ///
+    /// # Errors
+    /// Returns the same errors as the generator constructors.
+    pub fn build_single_thread(self) -> Result<single_thread_generator::SnowflakeGenerator<C>> {
+        Ok(single_thread_generator::SnowflakeGenerator::from_state(self.build_state()?))
+    }
+}
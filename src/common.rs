@@ -1,74 +1,1275 @@
-pub struct SnowflakeState {
-    pub time_since_epoch: i64,
-    pub worker_id: u16,
-    pub sequence: u16,
-    pub epoch: i64,
+#[cfg(feature = "std")]
+use crate::raw::FieldOrder;
+use crate::raw::RawSnowflake;
+
+/// The number of timestamp bits of the default 41/10/12 layout.
+pub const TIMESTAMP_BITS: usize = 41;
+/// The number of worker ID bits of the default layout.
+pub const WORKER_BITS: usize = 10;
+/// The number of sequence bits of the default layout.
+pub const SEQUENCE_BITS: usize = 12;
+/// The largest worker ID of the default layout.
+pub const MAX_WORKER_ID: u16 = (1 << WORKER_BITS) - 1;
+/// The largest sequence number of the default layout.
+pub const MAX_SEQUENCE: u16 = (1 << SEQUENCE_BITS) - 1;
+
+/// The clock-free state with the default layout.
+type DefaultRaw = RawSnowflake<TIMESTAMP_BITS, WORKER_BITS, SEQUENCE_BITS>;
+
+/// A source of wall-clock time used by the generators.
+pub trait ClockSource {
+    /// Current time in milliseconds since the Unix epoch.
+    fn now_millis(&self) -> i64;
+
+    /// Re-anchor the clock to the wall-clock time if it fell behind by more than
+    /// `threshold_ms`, never moving the time it reports backwards.
+    ///
+    /// Clocks reading the wall-clock on every call have nothing to correct, so the default
+    /// does nothing.
+    fn resync(&mut self, _threshold_ms: i64) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A synchronous generator of Snowflake IDs, to write code generic over the generators.
+pub trait SnowflakeSource {
+    /// Generate a new Snowflake ID, waiting for the clock when needed.
+    fn generate_id(&self) -> i64;
+}
+
+/// An asynchronous generator of Snowflake IDs, like [`SnowflakeSource`] for the async generators.
+///
+/// Implementations can be written as `async fn generate_id(&self) -> i64`.
+pub trait AsyncSnowflakeSource {
+    /// Generate a new Snowflake ID, waiting for the clock when needed.
+    fn generate_id(&self) -> impl core::future::Future<Output = i64> + Send;
+}
+
+/// Convert a `Duration::as_millis`/`as_micros` count to `i64`, saturating at `i64::MAX`
+/// instead of wrapping to a negative time like an `as` cast would.
+#[cfg(feature = "std")]
+pub(crate) fn saturating_i64(count: u128) -> i64 {
+    i64::try_from(count).unwrap_or(i64::MAX)
+}
+
+/// The default clock, reading `SystemTime` once and advancing it with a monotonic `Instant`.
+///
+/// Times too large for an `i64` saturate at `i64::MAX` rather than wrapping, so the
+/// generators report `TimestampOverflow` instead of going back in time.
+#[cfg(feature = "std")]
+pub struct SystemClock {
     instant: std::time::Instant,
     instant_timestamp: i64,
 }
 
-impl SnowflakeState {
-    pub fn new(epoch: i64, worker_id: u16) -> Result<Self> {
-        if worker_id > 0x3FF {
-            return Err(SnowflakeError::WorkerIdOutOfRange);
-        }
+#[cfg(feature = "std")]
+impl SystemClock {
+    /// # Errors
+    /// Returns `SnowflakeError::SystemTimeError` if the system time is before the Unix epoch.
+    pub fn new() -> Result<Self> {
         let instant = std::time::Instant::now();
         let instant_timestamp = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)
-            .map(|d| d.as_millis() as i64)
-            .map_err(|_| panic!("Failed to get instant timestamp"))?;
-        if instant_timestamp < epoch {
-            panic!("Epoch is in the future");
-        }
+            .map(|d| saturating_i64(d.as_millis()))
+            .map_err(|_| SnowflakeError::SystemTimeError)?;
         Ok(Self {
-            time_since_epoch: instant_timestamp - epoch,
+            instant,
             instant_timestamp,
+        })
+    }
+
+    /// Like `new`, but reads a system time before the Unix epoch as the epoch itself.
+    fn new_lossy() -> Self {
+        let instant = std::time::Instant::now();
+        let instant_timestamp = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| saturating_i64(d.as_millis()));
+        Self {
             instant,
-            worker_id,
-            epoch,
-            sequence: 0,
+            instant_timestamp,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ClockSource for SystemClock {
+    fn now_millis(&self) -> i64 {
+        let elapsed = saturating_i64(self.instant.elapsed().as_millis());
+        self.instant_timestamp.saturating_add(elapsed)
+    }
+
+    /// Re-read `SystemTime`, as `Instant` may not advance while the machine is suspended.
+    ///
+    /// Only moves forward: if the system time is behind, e.g. after an NTP step, the clock
+    /// keeps its current anchor.
+    fn resync(&mut self, threshold_ms: i64) -> Result<()> {
+        let wall = Self::new()?;
+        if wall.instant_timestamp - self.now_millis() > threshold_ms {
+            *self = wall;
+        }
+        Ok(())
+    }
+}
+
+/// A clock like [`SystemClock`] counting microseconds, for [`MicrosSnowflake`].
+///
+/// `now_millis` reports microseconds since the Unix epoch: the generators only subtract
+/// the epoch, so every time of a state using this clock is in microseconds.
+#[cfg(feature = "std")]
+pub struct MicrosClock {
+    instant: std::time::Instant,
+    instant_timestamp: i64,
+}
+
+#[cfg(feature = "std")]
+impl MicrosClock {
+    /// # Errors
+    /// Returns `SnowflakeError::SystemTimeError` if the system time is before the Unix epoch.
+    pub fn new() -> Result<Self> {
+        let instant = std::time::Instant::now();
+        let instant_timestamp = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)
+            .map(|d| saturating_i64(d.as_micros()))
+            .map_err(|_| SnowflakeError::SystemTimeError)?;
+        Ok(Self {
+            instant,
+            instant_timestamp,
         })
     }
+}
+
+#[cfg(feature = "std")]
+impl ClockSource for MicrosClock {
+    /// Current time in microseconds since the Unix epoch.
+    fn now_millis(&self) -> i64 {
+        let elapsed = saturating_i64(self.instant.elapsed().as_micros());
+        self.instant_timestamp.saturating_add(elapsed)
+    }
+
+    /// Like [`SystemClock::resync`], with the threshold still in milliseconds.
+    fn resync(&mut self, threshold_ms: i64) -> Result<()> {
+        let wall = Self::new()?;
+        if wall.instant_timestamp - self.now_millis() > threshold_ms.saturating_mul(1_000) {
+            *self = wall;
+        }
+        Ok(())
+    }
+}
+
+/// How far the clock of a [`SnowflakeState`] may fall behind the system time before
+/// `resync` re-anchors it, in milliseconds.
+#[cfg(feature = "std")]
+pub const DEFAULT_RESYNC_THRESHOLD_MS: i64 = 1_000;
+
+/// Snowflake generator state with a configurable bit layout, reading time from a [`ClockSource`].
+///
+/// The clock-free parts of the state live in [`RawSnowflake`], which this dereferences to.
+#[cfg(feature = "std")]
+pub struct SnowflakeState<
+    const TS_BITS: usize = TIMESTAMP_BITS,
+    const WORKER_BITS: usize = { crate::common::WORKER_BITS },
+    const SEQ_BITS: usize = SEQUENCE_BITS,
+    C: ClockSource = SystemClock,
+> {
+    raw: RawSnowflake<TS_BITS, WORKER_BITS, SEQ_BITS>,
+    clock: C,
+    backfill_sequences: std::collections::HashMap<i64, u16>,
+    #[cfg(feature = "dedup-check")]
+    worker_id_guard: Option<crate::registry::WorkerIdGuard>,
+    ids_generated: u64,
+    rollovers: u64,
+    waits: u64,
+    time_waited: std::time::Duration,
+    backoff: BackoffStrategy,
+    clock_mode: ClockMode,
+    forward_jump: ForwardJumpPolicy,
+    forward_jumps: u64,
+    /// The previous clock reading, to detect forward jumps.
+    last_clock_reading: i64,
+}
+
+/// What the generator does when it cannot generate an ID yet, because the sequence of the
+/// current millisecond is exhausted or the clock moved backwards.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BackoffStrategy {
+    /// Sleep for a millisecond, or until the clock caught up after moving backwards.
+    #[default]
+    SleepMs,
+    /// Spin, yielding to other threads, until the clock advances.
+    SpinYield,
+    /// Return the error instead of waiting.
+    Error,
+}
+
+/// Where the time of a [`SnowflakeState`] comes from after its clock was anchored.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ClockMode {
+    /// Advance with the monotonic clock, re-anchoring to the wall-clock on `resync` when it
+    /// fell behind, e.g. after the machine was suspended.
+    #[default]
+    WallClock,
+    /// Advance with the monotonic clock only, never reading the wall-clock again.
+    ///
+    /// The timestamps never reflect NTP corrections or suspends, but can never stall or
+    /// repeat during a leap second smear either.
+    MonotonicOnly,
+}
+
+/// What the generator does when the clock leaps forward, e.g. when NTP steps the system time.
+///
+/// IDs are only monotonic as long as the clock does not go back past the timestamp of the
+/// last ID: after a forward step, a later backward correction makes the generator wait
+/// (or fail with `SnowflakeError::ClockMovedBackwards`) until the clock caught up again.
+/// `Clamp` keeps the timestamps from following such a step at once, so a prompt correction
+/// costs a short wait at most, at the price of timestamps lagging the clock meanwhile.
+///
+/// Only the time between two readings of the clock is seen, so an idle generator is
+/// indistinguishable from a jump: pick a threshold above the expected idle time.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ForwardJumpPolicy {
+    /// Follow the clock wherever it goes.
+    #[default]
+    Follow,
+    /// Follow the clock, counting each leap of more than `max_ms` between two readings in
+    /// `SnowflakeStats::forward_jumps`.
+    Count { max_ms: i64 },
+    /// Count leaps like `Count`, and let the timestamps advance by at most `max_ms` (at least
+    /// 1) per ID until they caught up with the clock.
+    Clamp { max_ms: i64 },
+}
+
+/// Generation counters of a [`SnowflakeState`], returned by `stats`.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SnowflakeStats {
+    /// The total number of IDs generated, including backfilled ones.
+    pub ids_generated: u64,
+    /// The number of milliseconds whose sequence was used up.
+    pub rollovers: u64,
+    /// How far the timestamp of the last ID is ahead of the clock, in milliseconds.
+    /// Only non-zero after borrowing from the following milliseconds.
+    pub drift_ms: i64,
+    /// The number of forward jumps of the clock detected by the [`ForwardJumpPolicy`].
+    pub forward_jumps: u64,
+    /// The number of times generating had to wait for the clock, after a rollover or the
+    /// clock moving backwards, by sleeping or spinning as set by the [`BackoffStrategy`].
+    /// The async generators wait outside of the state, so their waits are not counted.
+    pub waits: u64,
+    /// The total time spent in those waits.
+    pub time_waited: std::time::Duration,
+}
+
+/// The length of a snapshot written by [`SnowflakeState::to_bytes`].
+#[cfg(feature = "std")]
+pub const SNAPSHOT_LEN: usize = 29;
+
+#[cfg(feature = "std")]
+const SNAPSHOT_VERSION: u8 = 2;
+
+/// The length of snapshots of version 1, which predate the field order and still restore.
+#[cfg(feature = "std")]
+const SNAPSHOT_V1_LEN: usize = 28;
+
+/// The classic 41/10/12 Snowflake layout.
+#[cfg(feature = "std")]
+pub type DefaultSnowflake<C = SystemClock> = SnowflakeState<TIMESTAMP_BITS, WORKER_BITS, SEQUENCE_BITS, C>;
+
+/// A 49/10/4 layout counting microseconds, for bursts beyond 4096 IDs per millisecond.
+///
+/// Each microsecond has its own 16 sequence numbers, up to 16,000 IDs per millisecond, and
+/// running out of them only waits for the next microsecond: [`MicrosSnowflake::new_micros`] spins
+/// instead of sleeping for a millisecond. The epoch, the timestamps of decomposed IDs and
+/// every other time of the state are in microseconds.
+///
+/// The 49 timestamp bits only last about 17.8 years from the epoch, instead of 69 years.
+/// The IDs are not comparable with those of the millisecond layouts.
+#[cfg(feature = "std")]
+pub type MicrosSnowflake<C = MicrosClock> = SnowflakeState<49, WORKER_BITS, 4, C>;
+
+#[cfg(feature = "std")]
+impl MicrosSnowflake {
+    /// Create a state counting microseconds since `epoch_micros`, spinning until the next
+    /// microsecond when its sequence is exhausted.
+    ///
+    /// # Errors
+    /// Fails like [`SnowflakeState::new`].
+    pub fn new_micros(epoch_micros: i64, worker_id: u16) -> Result<Self> {
+        let mut state = Self::with_clock(epoch_micros, worker_id, MicrosClock::new()?)?;
+        state.set_backoff_strategy(BackoffStrategy::SpinYield);
+        Ok(state)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const TS_BITS: usize, const WORKER_BITS: usize, const SEQ_BITS: usize> SnowflakeState<TS_BITS, WORKER_BITS, SEQ_BITS> {
+    /// # Errors
+    /// Fails like [`Self::with_clock`].
+    /// Returns `SnowflakeError::SystemTimeError` if the system time is before the Unix epoch.
+    pub fn new(epoch: i64, worker_id: u16) -> Result<Self> {
+        Self::with_clock(epoch, worker_id, SystemClock::new()?)
+    }
+
+    /// Like `new`, but without checking the worker ID and epoch, e.g. to construct many
+    /// short-lived generators from values validated once up front.
+    ///
+    /// The clock is still anchored to the system time. The worker ID is not registered with
+    /// the `dedup-check` feature.
+    ///
+    /// # Safety
+    /// The worker_id must fit the layout (0-1023 for the default layout), the epoch must not
+    /// be in the future and the system time must not be before the Unix epoch.
+    /// See [`RawSnowflake::new_unchecked`].
+    #[must_use]
+    pub unsafe fn new_unchecked(epoch: i64, worker_id: u16) -> Self {
+        let clock = SystemClock::new_lossy();
+        let now = clock.now_millis();
+        // SAFETY: upheld by the caller.
+        let raw = unsafe { RawSnowflake::new_unchecked(epoch, worker_id, now) };
+        Self::from_raw(raw, clock, now)
+    }
+
+    /// Like `new`, but also rejects the highest worker ID of the layout (1023 for the
+    /// default layout), so it can be reserved as a sentinel for unconfigured nodes.
+    ///
+    /// # Errors
+    /// Returns `SnowflakeError::ReservedWorkerId` if the worker_id is the reserved value.
+    /// Otherwise fails like `new`.
+    pub fn new_strict(epoch: i64, worker_id: u16) -> Result<Self> {
+        if worker_id as i64 == RawSnowflake::<TS_BITS, WORKER_BITS, SEQ_BITS>::WORKER_MASK {
+            return Err(SnowflakeError::ReservedWorkerId);
+        }
+        Self::new(epoch, worker_id)
+    }
+
+    /// Like `new`, but resumes the sequence of the current millisecond at `initial_sequence`,
+    /// e.g. the value of [`Self::current_sequence`] persisted before a restart.
+    ///
+    /// # Errors
+    /// Returns `SnowflakeError::SequenceOutOfRange` if the initial sequence does not fit the
+    /// layout (0-4095 for the default layout). Otherwise fails like `new`.
+    pub fn with_sequence_start(epoch: i64, worker_id: u16, initial_sequence: u16) -> Result<Self> {
+        if initial_sequence as i64 > RawSnowflake::<TS_BITS, WORKER_BITS, SEQ_BITS>::SEQUENCE_MASK {
+            return Err(SnowflakeError::SequenceOutOfRange);
+        }
+        let mut state = Self::new(epoch, worker_id)?;
+        state.raw.sequence = initial_sequence;
+        Ok(state)
+    }
+
+    /// Restore a state saved with [`Self::to_bytes`], reading the current time from a
+    /// fresh `SystemClock`.
+    ///
+    /// # Errors
+    /// Fails like [`Self::from_bytes_with_clock`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Self::from_bytes_with_clock(bytes, SystemClock::new()?)
+    }
+
+    /// Create a state generating IDs of `tenant`, served by the node `node_id`, with the
+    /// worker bits split as set by `layout`.
+    ///
+    /// # Errors
+    /// Fails like [`TenantLayout::worker_id`](crate::tenant::TenantLayout::worker_id) and `new`.
+    pub fn with_tenant(
+        epoch: i64,
+        layout: crate::tenant::TenantLayout<WORKER_BITS>,
+        tenant: crate::tenant::TenantId,
+        node_id: u16,
+    ) -> Result<Self> {
+        Self::new(epoch, layout.worker_id(tenant, node_id)?)
+    }
+}
+
+#[cfg(feature = "std")]
+impl SnowflakeState {
+    /// Create a state using the Twitter-style split of the worker bits into
+    /// 5 datacenter bits followed by 5 worker bits.
+    pub fn with_datacenter(epoch: i64, datacenter_id: u8, worker_id: u8) -> Result<Self> {
+        Self::new(epoch, pack_datacenter(datacenter_id, worker_id)?)
+    }
+}
+
+/// Pack a datacenter ID (0-31) and a worker ID (0-31) into the 10 worker bits.
+pub fn pack_datacenter(datacenter_id: u8, worker_id: u8) -> Result<u16> {
+    if datacenter_id > 0x1F {
+        return Err(SnowflakeError::DatacenterIdOutOfRange);
+    }
+    if worker_id > 0x1F {
+        return Err(SnowflakeError::WorkerIdOutOfRange);
+    }
+    Ok(((datacenter_id as u16) << 5) | worker_id as u16)
+}
+
+#[cfg(feature = "std")]
+impl<const TS_BITS: usize, const WORKER_BITS: usize, const SEQ_BITS: usize, C: ClockSource> SnowflakeState<TS_BITS, WORKER_BITS, SEQ_BITS, C> {
+    /// # Errors
+    /// Fails like [`RawSnowflake::new`] at the clock's current time.
+    /// Returns `SnowflakeError::DuplicateWorkerId` with the `dedup-check` feature if another
+    /// live state or generator already uses the worker_id in this process.
+    pub fn with_clock(epoch: i64, worker_id: u16, clock: C) -> Result<Self> {
+        Self::with_clock_and_stride(epoch, worker_id, clock, 1, 0)
+    }
+
+    /// Like `with_clock` followed by `sequence_stride`, but only registers the stride with
+    /// the `dedup-check` feature, so generators sharing the worker ID with disjoint strides
+    /// can be created.
+    pub(crate) fn with_clock_and_stride(epoch: i64, worker_id: u16, clock: C, step: u16, offset: u16) -> Result<Self> {
+        let now = clock.now_millis();
+        let raw = RawSnowflake::new(epoch, worker_id, now)?.with_sequence_stride(step, offset)?;
+        #[allow(unused_mut)]
+        let mut state = Self::from_raw(raw, clock, now);
+        #[cfg(feature = "dedup-check")]
+        state.register()?;
+        Ok(state)
+    }
 
-    fn to_i64(&self) -> i64 {
-        ((self.time_since_epoch << 22) | ((self.worker_id as i64) << 12) | (self.sequence as i64)) & 0x7FFFFFFFFFFFFFFF
+    /// Hold the worker ID and sequence stride in the registry of the `dedup-check` feature,
+    /// releasing what the state held before.
+    #[cfg(feature = "dedup-check")]
+    fn register(&mut self) -> Result<()> {
+        self.worker_id_guard = None;
+        let guard = crate::registry::WorkerIdGuard::acquire_stride(self.raw.worker_id, self.raw.sequence_step, self.raw.sequence_offset)?;
+        self.worker_id_guard = Some(guard);
+        Ok(())
     }
 
-    fn get_time_since_epoch(&self) -> i64 {
-        self.instant_timestamp + self.instant.elapsed().as_millis() as i64 - self.epoch
+    fn from_raw(raw: RawSnowflake<TS_BITS, WORKER_BITS, SEQ_BITS>, clock: C, now: i64) -> Self {
+        Self {
+            raw,
+            clock,
+            backfill_sequences: std::collections::HashMap::new(),
+            #[cfg(feature = "dedup-check")]
+            worker_id_guard: None,
+            ids_generated: 0,
+            rollovers: 0,
+            waits: 0,
+            time_waited: std::time::Duration::ZERO,
+            backoff: BackoffStrategy::default(),
+            clock_mode: ClockMode::default(),
+            forward_jump: ForwardJumpPolicy::default(),
+            forward_jumps: 0,
+            last_clock_reading: now,
+        }
+    }
+
+    /// Save the epoch, worker ID, last timestamp, sequence, sequence stride and field order,
+    /// so that a restarted generator can resume without reusing IDs of the last millisecond.
+    ///
+    /// The layout is a version byte, the three bit widths, then the fields in little endian.
+    pub fn to_bytes(&self) -> [u8; SNAPSHOT_LEN] {
+        let mut bytes = [0; SNAPSHOT_LEN];
+        bytes[0] = SNAPSHOT_VERSION;
+        bytes[1] = TS_BITS as u8;
+        bytes[2] = WORKER_BITS as u8;
+        bytes[3] = SEQ_BITS as u8;
+        bytes[4..12].copy_from_slice(&self.raw.epoch.to_le_bytes());
+        bytes[12..14].copy_from_slice(&self.raw.worker_id.to_le_bytes());
+        bytes[14..22].copy_from_slice(&self.raw.time_since_epoch.to_le_bytes());
+        bytes[22..24].copy_from_slice(&self.raw.sequence.to_le_bytes());
+        bytes[24..26].copy_from_slice(&self.raw.sequence_step.to_le_bytes());
+        bytes[26..28].copy_from_slice(&self.raw.sequence_offset.to_le_bytes());
+        bytes[28] = match self.raw.field_order {
+            FieldOrder::WorkerThenSequence => 0,
+            FieldOrder::SequenceThenWorker => 1,
+        };
+        bytes
     }
 
+    /// Restore a state saved with [`Self::to_bytes`], driven by a custom clock.
+    ///
+    /// The saved timestamp and sequence are kept, so the next ID never repeats one generated
+    /// before the snapshot, even if the clock is still in the same millisecond or behind it.
+    ///
+    /// # Errors
+    /// Returns `SnowflakeError::InvalidSnapshot` if the bytes are not a snapshot of this
+    /// version and layout, or hold invalid values.
+    /// Returns `SnowflakeError::EpochInFuture` if the saved epoch is in the future.
+    pub fn from_bytes_with_clock(bytes: &[u8], clock: C) -> Result<Self> {
+        let field_order = match (bytes.len(), bytes.first()) {
+            (SNAPSHOT_LEN, Some(&SNAPSHOT_VERSION)) => match bytes[28] {
+                0 => FieldOrder::WorkerThenSequence,
+                1 => FieldOrder::SequenceThenWorker,
+                _ => return Err(SnowflakeError::InvalidSnapshot),
+            },
+            (SNAPSHOT_V1_LEN, Some(1)) => FieldOrder::WorkerThenSequence,
+            _ => return Err(SnowflakeError::InvalidSnapshot),
+        };
+        if bytes[1..4] != [TS_BITS as u8, WORKER_BITS as u8, SEQ_BITS as u8] {
+            return Err(SnowflakeError::InvalidSnapshot);
+        }
+        let i64_at = |i: usize| i64::from_le_bytes(bytes[i..i + 8].try_into().expect("8 bytes"));
+        let u16_at = |i: usize| u16::from_le_bytes([bytes[i], bytes[i + 1]]);
+        let (time_since_epoch, sequence) = (i64_at(14), u16_at(22));
+        let mut state = match Self::with_clock_and_stride(i64_at(4), u16_at(12), clock, u16_at(24), u16_at(26)) {
+            Err(SnowflakeError::WorkerIdOutOfRange | SnowflakeError::InvalidSequenceStride) => {
+                return Err(SnowflakeError::InvalidSnapshot)
+            }
+            state => state?,
+        };
+        let max_sequence = RawSnowflake::<TS_BITS, WORKER_BITS, SEQ_BITS>::SEQUENCE_MASK + state.raw.sequence_step as i64;
+        if time_since_epoch < 0 || sequence as i64 > max_sequence {
+            return Err(SnowflakeError::InvalidSnapshot);
+        }
+        state.raw.time_since_epoch = time_since_epoch;
+        state.raw.sequence = sequence;
+        state.raw.field_order = field_order;
+        Ok(state)
+    }
+
+    /// Re-anchor the clock to the wall-clock time if it drifted behind by more than
+    /// [`DEFAULT_RESYNC_THRESHOLD_MS`], e.g. after the machine was suspended.
+    ///
+    /// The clock only ever moves forward, so IDs stay monotonic. Call this periodically in
+    /// long-running processes. Does nothing with [`ClockMode::MonotonicOnly`].
+    ///
+    /// # Errors
+    /// Returns `SnowflakeError::SystemTimeError` if the system time is before the Unix epoch.
+    pub fn resync(&mut self) -> Result<()> {
+        match self.clock_mode {
+            ClockMode::WallClock => self.clock.resync(DEFAULT_RESYNC_THRESHOLD_MS),
+            ClockMode::MonotonicOnly => Ok(()),
+        }
+    }
+
+    /// Set whether `resync` may re-anchor the clock to the wall-clock. See [`ClockMode`].
+    pub fn set_clock_mode(&mut self, mode: ClockMode) {
+        self.clock_mode = mode;
+    }
+
+    /// Set what `generate_id` and `generate_id_with_backoff` do when they have to wait.
+    pub fn set_backoff_strategy(&mut self, backoff: BackoffStrategy) {
+        self.backoff = backoff;
+    }
+
+    /// Set how leaps of the clock are detected and smoothed. See [`ForwardJumpPolicy`].
+    pub fn set_forward_jump_policy(&mut self, policy: ForwardJumpPolicy) {
+        self.forward_jump = policy;
+        self.last_clock_reading = self.clock.now_millis();
+    }
+
+    /// Read the clock, counting and clamping forward jumps as set by the policy.
+    fn read_clock(&mut self) -> i64 {
+        let now = self.clock.now_millis();
+        let (ForwardJumpPolicy::Count { max_ms } | ForwardJumpPolicy::Clamp { max_ms }) = self.forward_jump else {
+            return now;
+        };
+        if now.saturating_sub(self.last_clock_reading) > max_ms {
+            self.forward_jumps += 1;
+        }
+        self.last_clock_reading = now;
+        self.clamp_forward(now)
+    }
+
+    fn clamp_forward(&self, now: i64) -> i64 {
+        match self.forward_jump {
+            ForwardJumpPolicy::Clamp { max_ms } => {
+                let last = self.raw.epoch + self.raw.time_since_epoch;
+                now.min(last.saturating_add(max_ms.max(1)))
+            }
+            _ => now,
+        }
+    }
+
+    /// Generate a new Snowflake ID, waiting as set by the backoff strategy when needed.
+    ///
+    /// As this cannot fail, `BackoffStrategy::Error` behaves like `BackoffStrategy::SleepMs`
+    /// here. Use `generate_id_with_backoff` to get the error instead.
+    ///
+    /// # Panics
+    /// Panics once the time since the epoch no longer fits the timestamp bits, as waiting
+    /// would never help. Use `try_generate_id` to get `SnowflakeError::TimestampOverflow` instead.
+    ///
+    /// A dropped ID is almost always a bug, so the result must be used:
+    ///
+    /// ```compile_fail
+    /// #![deny(unused_must_use)]
+    /// let mut state = snowflake_id_generator::DefaultSnowflake::new(0, 1).unwrap();
+    /// state.generate_id();
+    /// ```
+    #[must_use]
     pub fn generate_id(&mut self) -> i64 {
-        let current_time = self.get_time_since_epoch();
-        if self.time_since_epoch == current_time {
-            if self.sequence > 0xFFF {
-                std::thread::sleep(std::time::Duration::from_millis(1));
-                self.time_since_epoch = self.get_time_since_epoch();
-                self.sequence = 0;
+        self.generate_waiting(Self::try_generate_id)
+    }
+
+    /// Like `generate_id`, but uses all 64 bits. See [`RawSnowflake::generate_id_u64`].
+    #[must_use]
+    pub fn generate_id_u64(&mut self) -> u64 {
+        self.generate_waiting(Self::try_generate_id_u64)
+    }
+
+    /// Like `generate_id`, but embeds a checksum in the low sequence bits, to be checked
+    /// with [`verify_checksum`]. See [`RawSnowflake::generate_id_with_checksum`].
+    ///
+    /// # Panics
+    /// Panics if a sequence stride is set. Otherwise panics like `generate_id`.
+    #[must_use]
+    pub fn generate_id_with_checksum(&mut self) -> i64 {
+        self.generate_waiting(Self::try_generate_id_with_checksum)
+    }
+
+    fn generate_waiting<T>(&mut self, try_generate: fn(&mut Self) -> Result<T>) -> T {
+        let backoff = match self.backoff {
+            BackoffStrategy::Error => BackoffStrategy::SleepMs,
+            backoff => backoff,
+        };
+        match self.generate_using(backoff, try_generate) {
+            Ok(id) => id,
+            Err(e) => panic!("Cannot generate an ID: {}", e),
+        }
+    }
+
+    /// Generate a new Snowflake ID, waiting as set by the backoff strategy when needed.
+    ///
+    /// # Errors
+    /// With `BackoffStrategy::Error`, returns `SnowflakeError::SequenceExhausted` or
+    /// `SnowflakeError::ClockMovedBackwards` instead of waiting.
+    /// With any strategy, returns `SnowflakeError::TimestampOverflow` once the time since
+    /// the epoch no longer fits the timestamp bits.
+    pub fn generate_id_with_backoff(&mut self) -> Result<i64> {
+        self.generate_using(self.backoff, Self::try_generate_id)
+    }
+
+    fn generate_using<T>(
+        &mut self,
+        backoff: BackoffStrategy,
+        mut try_generate: impl FnMut(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        let mut waiting_since = None;
+        let result = loop {
+            match (try_generate(self), backoff) {
+                (Ok(id), _) => break Ok(id),
+                (
+                    Err(
+                        e @ (SnowflakeError::TimestampOverflow
+                        | SnowflakeError::InvalidSequenceStride
+                        | SnowflakeError::UnsupportedFieldOrder
+                        | SnowflakeError::BlockTooLarge
+                        | SnowflakeError::WorkerIdOutOfRange),
+                    ),
+                    _,
+                )
+                | (Err(e), BackoffStrategy::Error) => break Err(e),
+                (Err(e), _) => {
+                    waiting_since.get_or_insert_with(std::time::Instant::now);
+                    match (e, backoff) {
+                        (_, BackoffStrategy::SpinYield) => {
+                            std::hint::spin_loop();
+                            std::thread::yield_now();
+                        }
+                        (SnowflakeError::ClockMovedBackwards { by_ms }, _) => {
+                            std::thread::sleep(std::time::Duration::from_millis(by_ms as u64))
+                        }
+                        _ => std::thread::sleep(std::time::Duration::from_millis(1)),
+                    }
+                }
             }
-        } else {
-            self.time_since_epoch = current_time;
-            self.sequence = 0;
+        };
+        if let Some(since) = waiting_since {
+            self.record_wait(since);
+        }
+        result
+    }
+
+    fn record_wait(&mut self, since: std::time::Instant) {
+        self.waits += 1;
+        self.time_waited += since.elapsed();
+    }
+
+    /// Reserve `n` consecutive IDs of one millisecond under a single clock reading.
+    /// See [`RawSnowflake::reserve_block`].
+    ///
+    /// # Errors
+    /// Fails like [`RawSnowflake::reserve_block`].
+    pub fn try_reserve(&mut self, n: u16) -> Result<IdBlock> {
+        let now = self.read_clock();
+        let block = self.raw.reserve_block(now, n)?;
+        self.record_generated(n as usize);
+        Ok(block)
+    }
+
+    /// Reserve `n` consecutive IDs of one millisecond, waiting as set by the backoff strategy
+    /// when fewer are left in the current millisecond.
+    ///
+    /// # Errors
+    /// Returns `SnowflakeError::BlockTooLarge` if `n` exceeds the IDs of a whole millisecond.
+    /// Otherwise fails like `generate_id_with_backoff`.
+    pub fn reserve(&mut self, n: u16) -> Result<IdBlock> {
+        self.generate_using(self.backoff, |state| state.try_reserve(n))
+    }
+
+    /// Reserve `n` IDs as ranges of consecutive IDs, one per millisecond at most, sleeping
+    /// until the next millisecond whenever the sequence is exhausted.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn reserve_ids(&mut self, n: usize) -> Vec<(i64, usize)> {
+        let mut ranges = Vec::new();
+        let mut remaining = n;
+        while remaining > 0 {
+            let now = self.read_clock();
+            let since = std::time::Instant::now();
+            match self.raw.reserve_ids(now, remaining) {
+                Ok((first, count)) => {
+                    self.record_generated(count);
+                    ranges.push((first, count));
+                    remaining -= count;
+                    continue;
+                }
+                Err(SnowflakeError::ClockMovedBackwards { by_ms }) => {
+                    std::thread::sleep(std::time::Duration::from_millis(by_ms as u64))
+                }
+                Err(e @ SnowflakeError::TimestampOverflow) => panic!("Cannot generate an ID: {}", e),
+                Err(_) => std::thread::sleep(std::time::Duration::from_millis(1)),
+            }
+            self.record_wait(since);
+        }
+        ranges
+    }
+
+    /// Generate a new Snowflake ID without ever sleeping, borrowing IDs from the following
+    /// milliseconds when the sequence of the current one is exhausted.
+    ///
+    /// During a burst, timestamps run ahead of the clock by one millisecond for every 4096
+    /// IDs (with the default layout) generated beyond the capacity of the elapsed
+    /// milliseconds, and fall back in step once the burst is over. A clock moving backwards
+    /// is absorbed the same way. See [`RawSnowflake::generate_id_monotonic`].
+    ///
+    /// # Panics
+    /// Panics once the timestamp no longer fits the timestamp bits. Use
+    /// `try_generate_id_monotonic` to get `SnowflakeError::TimestampOverflow` instead.
+    #[must_use]
+    pub fn generate_id_monotonic(&mut self) -> i64 {
+        match self.try_generate_id_monotonic() {
+            Ok(id) => id,
+            Err(e) => panic!("Cannot generate an ID: {}", e),
+        }
+    }
+
+    /// Like `generate_id_monotonic`, but returns the error instead of panicking.
+    ///
+    /// # Errors
+    /// Fails like [`RawSnowflake::generate_id_monotonic`].
+    pub fn try_generate_id_monotonic(&mut self) -> Result<i64> {
+        let now = self.read_clock();
+        let id = self.raw.generate_id_monotonic(now)?;
+        self.record_generated(1);
+        Ok(id)
+    }
+
+    /// Hand out only every `step`-th sequence number of each millisecond, starting at `offset`.
+    ///
+    /// Generators sharing a worker ID but using distinct offsets with the same step produce
+    /// disjoint IDs, e.g. one per database shard. See [`RawSnowflake::with_sequence_stride`].
+    ///
+    /// # Errors
+    /// Returns `SnowflakeError::InvalidSequenceStride` if `step` is 0 or does not divide
+    /// the sequence space (4096 for the default layout), or if `offset` is not below `step`.
+    pub fn sequence_stride(mut self, step: u16, offset: u16) -> Result<Self> {
+        self.raw = self.raw.with_sequence_stride(step, offset)?;
+        #[cfg(feature = "dedup-check")]
+        if self.worker_id_guard.is_some() {
+            self.register()?;
+        }
+        Ok(self)
+    }
+
+    /// Place the worker ID and sequence fields in the given order. See [`FieldOrder`].
+    pub fn field_order(mut self, order: FieldOrder) -> Self {
+        self.raw = self.raw.with_field_order(order);
+        self
+    }
+
+    /// Generate a new Snowflake ID without blocking.
+    ///
+    /// Returns `SnowflakeError::SequenceExhausted` if all sequence numbers for the
+    /// current millisecond have been used. The state is left untouched in that case,
+    /// so the call can simply be retried later.
+    ///
+    /// Returns `SnowflakeError::ClockMovedBackwards` if the clock reports a time earlier
+    /// than the last generated ID, instead of emitting a non-monotonic ID.
+    ///
+    /// Returns `SnowflakeError::TimestampOverflow` once the time since the epoch no longer
+    /// fits the timestamp bits.
+    pub fn try_generate_id(&mut self) -> Result<i64> {
+        let now = self.read_clock();
+        let id = self.raw.generate_id(now)?;
+        self.record_generated(1);
+        Ok(id)
+    }
+
+    /// Generate a new Snowflake ID stamped with `worker_id` instead of this state's worker ID,
+    /// waiting as set by the backoff strategy when needed. The state keeps its own worker ID.
+    /// See [`RawSnowflake::generate_id_as`].
+    ///
+    /// # Errors
+    /// Returns `SnowflakeError::WorkerIdOutOfRange` if the worker_id does not fit the layout.
+    /// Otherwise fails like `generate_id_with_backoff`.
+    pub fn generate_id_as(&mut self, worker_id: u16) -> Result<i64> {
+        self.generate_using(self.backoff, |state| {
+            let now = state.read_clock();
+            let id = state.raw.generate_id_as(now, worker_id)?;
+            state.record_generated(1);
+            Ok(id)
+        })
+    }
+
+    /// Generate a new Snowflake ID strictly greater than `floor`, e.g. to place a record
+    /// after a known one.
+    ///
+    /// The sequence numbers of the millisecond of `floor` that would not give a greater ID
+    /// are skipped, without generating them. While the clock is behind the first millisecond
+    /// holding a greater ID, waits as set by the backoff strategy. See
+    /// [`RawSnowflake::generate_id_after`].
+    ///
+    /// # Errors
+    /// Returns `SnowflakeError::TimestampInFuture` if `floor` is more than
+    /// [`DEFAULT_MAX_FUTURE_MS`] ahead of the clock, or with `BackoffStrategy::Error` if the
+    /// clock has not reached the first millisecond holding a greater ID.
+    /// Otherwise fails like `generate_id_with_backoff`.
+    pub fn generate_id_after(&mut self, floor: i64) -> Result<i64> {
+        let floor_timestamp = floor >> RawSnowflake::<TS_BITS, WORKER_BITS, SEQ_BITS>::TIMESTAMP_SHIFT;
+        if floor_timestamp - (self.read_clock() - self.raw.epoch) > DEFAULT_MAX_FUTURE_MS {
+            return Err(SnowflakeError::TimestampInFuture);
+        }
+        self.generate_using(self.backoff, |state| {
+            let now = state.read_clock();
+            let id = state.raw.generate_id_after(now, floor)?;
+            state.record_generated(1);
+            Ok(id)
+        })
+    }
+
+    /// Like `try_generate_id`, but embeds a checksum in the low sequence bits.
+    /// See [`RawSnowflake::generate_id_with_checksum`].
+    ///
+    /// # Errors
+    /// Fails like [`RawSnowflake::generate_id_with_checksum`].
+    pub fn try_generate_id_with_checksum(&mut self) -> Result<i64> {
+        let now = self.read_clock();
+        let id = self.raw.generate_id_with_checksum(now)?;
+        self.record_generated(1);
+        Ok(id)
+    }
+
+    /// Like `try_generate_id`, but uses all 64 bits. See [`RawSnowflake::generate_id_u64`].
+    ///
+    /// # Errors
+    /// Fails like `try_generate_id`.
+    pub fn try_generate_id_u64(&mut self) -> Result<u64> {
+        let now = self.read_clock();
+        let id = self.raw.generate_id_u64(now)?;
+        self.record_generated(1);
+        Ok(id)
+    }
+
+    fn record_generated(&mut self, count: usize) {
+        self.ids_generated += count as u64;
+        if self.raw.sequence as i64 > RawSnowflake::<TS_BITS, WORKER_BITS, SEQ_BITS>::SEQUENCE_MASK {
+            self.rollovers += 1;
         }
-        let id = self.to_i64();
+    }
 
-        self.sequence += 1;
-        id
+    /// How far the timestamp of the last ID is from the clock, in milliseconds: positive
+    /// while IDs borrowed by `generate_id_monotonic` run ahead of it, zero or negative once
+    /// the clock caught up.
+    pub fn drift_ms(&self) -> i64 {
+        self.raw.time_since_epoch + self.raw.epoch - self.clock.now_millis()
     }
 
-    pub fn decompose(&self, id: i64) -> SnowflakeDecomposed {
-        let timestamp = (id >> 22) + self.epoch;
-        let worker_id = ((id >> 12) & 0x3FF) as u16;
-        let sequence = (id & 0xFFF) as u16;
+    /// Counters of the IDs generated so far, and the current drift from the clock.
+    pub fn stats(&self) -> SnowflakeStats {
+        SnowflakeStats {
+            ids_generated: self.ids_generated,
+            rollovers: self.rollovers,
+            drift_ms: self.drift_ms().max(0),
+            forward_jumps: self.forward_jumps,
+            waits: self.waits,
+            time_waited: self.time_waited,
+        }
+    }
 
-        SnowflakeDecomposed {
-            timestamp,
-            worker_id,
-            sequence,
+    /// Generate an ID for an explicit past timestamp, for backfilling historical records.
+    ///
+    /// Each backfilled millisecond keeps its own sequence counter, so repeated calls with
+    /// the same timestamp yield distinct IDs. Only timestamps before the millisecond of the
+    /// last live ID are accepted, so backfilled IDs can never collide with generated ones.
+    ///
+    /// # Errors
+    /// Returns `SnowflakeError::TimestampBeforeEpoch` if the timestamp is before the epoch.
+    /// Returns `SnowflakeError::TimestampNotInPast` if the timestamp is not in the past.
+    /// Returns `SnowflakeError::SequenceExhausted` if the sequence for that millisecond is used up.
+    pub fn generate_id_at(&mut self, timestamp_ms: i64) -> Result<i64> {
+        let time_since_epoch = timestamp_ms - self.raw.epoch;
+        if time_since_epoch < 0 {
+            return Err(SnowflakeError::TimestampBeforeEpoch);
         }
+        if time_since_epoch >= self.raw.time_since_epoch {
+            return Err(SnowflakeError::TimestampNotInPast);
+        }
+        let sequence = self.backfill_sequences.entry(time_since_epoch).or_insert(self.raw.sequence_offset);
+        if *sequence as i64 > RawSnowflake::<TS_BITS, WORKER_BITS, SEQ_BITS>::SEQUENCE_MASK {
+            return Err(SnowflakeError::SequenceExhausted);
+        }
+        let id = self.raw.pack(time_since_epoch, *sequence);
+        *sequence += self.raw.sequence_step;
+        self.ids_generated += 1;
+        Ok(id)
+    }
+
+    /// The custom epoch in milliseconds, e.g. for [`decompose_snowflake`].
+    pub fn epoch(&self) -> i64 {
+        self.raw.epoch
+    }
+
+    /// The sequence number the next ID of the current millisecond will use.
+    ///
+    /// Exceeds the sequence mask once the current millisecond is exhausted.
+    pub fn current_sequence(&self) -> u16 {
+        self.raw.sequence
+    }
+
+    /// The ID the next call to `generate_id` would return, without consuming it.
+    ///
+    /// Reads the clock the same way `generate_id` does. If the sequence for the current
+    /// millisecond is exhausted, this is the first ID of the following millisecond.
+    #[must_use]
+    pub fn peek_id(&self) -> i64 {
+        self.raw.peek_id(self.clamp_forward(self.clock.now_millis()))
+    }
+
+    /// How many more IDs fit the current millisecond before `generate_id` has to wait,
+    /// e.g. to shed load or batch ahead of time. See [`RawSnowflake::remaining_in_window`].
+    pub fn remaining_in_window(&self) -> u16 {
+        self.raw.remaining_in_window(self.clamp_forward(self.clock.now_millis()))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const TS_BITS: usize, const WORKER_BITS: usize, const SEQ_BITS: usize, C: ClockSource> std::ops::Deref
+    for SnowflakeState<TS_BITS, WORKER_BITS, SEQ_BITS, C>
+{
+    type Target = RawSnowflake<TS_BITS, WORKER_BITS, SEQ_BITS>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.raw
     }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg(feature = "std")]
+impl<const TS_BITS: usize, const WORKER_BITS: usize, const SEQ_BITS: usize, C: ClockSource> std::ops::DerefMut
+    for SnowflakeState<TS_BITS, WORKER_BITS, SEQ_BITS, C>
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.raw
+    }
+}
+
+/// Decompose an ID generated with the default 41/10/12 layout.
+///
+/// # Errors
+/// Returns `SnowflakeError::NegativeId` if the sign bit is set.
+/// Returns `SnowflakeError::TimestampOverflow` if adding the epoch to the timestamp overflows.
+pub fn decompose_snowflake(id: i64, epoch: i64) -> Result<SnowflakeDecomposed> {
+    DefaultRaw::try_decompose_with_epoch(id, epoch)
+}
+
+/// The timestamp of an ID with the default 41/10/12 layout, in milliseconds since the Unix
+/// epoch, without decoding the worker ID and sequence, e.g. to filter IDs by time range.
+///
+/// # Errors
+/// Returns `SnowflakeError::NegativeId` if the sign bit is set.
+/// Returns `SnowflakeError::TimestampOverflow` if adding the epoch to the timestamp overflows.
+pub fn timestamp_of(id: i64, epoch: i64) -> Result<i64> {
+    if id < 0 {
+        return Err(SnowflakeError::NegativeId);
+    }
+    (id >> DefaultRaw::TIMESTAMP_SHIFT).checked_add(epoch).ok_or(SnowflakeError::TimestampOverflow)
+}
+
+/// Pack a decomposed ID back into an ID with the default 41/10/12 layout, the inverse of
+/// [`decompose_snowflake`].
+///
+/// # Errors
+/// Returns `SnowflakeError::TimestampBeforeEpoch` if the timestamp is before the epoch.
+/// Returns `SnowflakeError::TimestampOverflow` if the time since the epoch does not fit the timestamp bits.
+/// Returns `SnowflakeError::WorkerIdOutOfRange` if the worker ID does not fit the worker bits.
+/// Returns `SnowflakeError::SequenceOutOfRange` if the sequence does not fit the sequence bits.
+pub fn compose_snowflake(decomposed: &SnowflakeDecomposed, epoch: i64) -> Result<i64> {
+    DefaultRaw::try_compose_with_epoch(decomposed, epoch)
+}
+
+/// The epoch of Discord snowflakes, the first second of 2015 (UTC), in milliseconds.
+pub const fn discord_epoch() -> i64 {
+    1_420_070_400_000
+}
+
+/// Decompose a Discord snowflake, e.g. a user or message ID.
+///
+/// Discord IDs share the default layout, so `worker_id` holds Discord's internal worker ID
+/// in its high 5 bits and process ID in its low 5 bits, readable with
+/// [`SnowflakeDecomposed::datacenter_id`] and [`SnowflakeDecomposed::datacenter_worker_id`].
+///
+/// # Errors
+/// Fails like [`decompose_snowflake`].
+pub fn decompose_discord(id: i64) -> Result<SnowflakeDecomposed> {
+    decompose_snowflake(id, discord_epoch())
+}
+
+/// How far ahead of the system time an ID accepted by [`validate_id`] may be, in milliseconds.
+#[cfg(feature = "std")]
+pub const DEFAULT_MAX_FUTURE_MS: i64 = 60_000;
+
+/// Decompose an untrusted ID of the default layout and check it could have been generated
+/// by one of our workers, allowing up to [`DEFAULT_MAX_FUTURE_MS`] of clock skew.
+///
+/// # Errors
+/// Fails like [`validate_id_with_tolerance`].
+#[cfg(feature = "std")]
+pub fn validate_id(id: i64, epoch: i64, max_worker: u16) -> Result<SnowflakeDecomposed> {
+    validate_id_with_tolerance(id, epoch, max_worker, DEFAULT_MAX_FUTURE_MS)
+}
+
+/// Like [`validate_id`], with an explicit tolerance for IDs ahead of the system time.
+///
+/// # Errors
+/// Fails like [`decompose_snowflake`].
+/// Returns `SnowflakeError::WorkerIdOutOfRange` if the worker ID is above `max_worker`.
+/// Returns `SnowflakeError::TimestampInFuture` if the timestamp is more than `max_future_ms`
+/// ahead of the system time.
+/// Returns `SnowflakeError::SystemTimeError` if the system time is before the Unix epoch.
+#[cfg(feature = "std")]
+pub fn validate_id_with_tolerance(id: i64, epoch: i64, max_worker: u16, max_future_ms: i64) -> Result<SnowflakeDecomposed> {
+    let decomposed = decompose_snowflake(id, epoch)?;
+    if decomposed.worker_id > max_worker {
+        return Err(SnowflakeError::WorkerIdOutOfRange);
+    }
+    let now = SystemClock::new()?.now_millis();
+    if decomposed.timestamp > now.saturating_add(max_future_ms) {
+        return Err(SnowflakeError::TimestampInFuture);
+    }
+    Ok(decomposed)
+}
+
+/// Heuristically tell whether an i64 key could be a Snowflake ID of the default layout.
+///
+/// Returns false for negative IDs, IDs whose timestamp is the epoch itself (every value
+/// below 2^22, such as small auto-increment keys) and IDs more than
+/// [`DEFAULT_MAX_FUTURE_MS`] ahead of the system time.
+///
+/// This cannot detect much more: every value between 2^22 and the ID of the current
+/// millisecond decodes to a plausible timestamp, so an auto-increment key above roughly
+/// 4.2 million is almost always a false positive. With an epoch `d` days in the past, about
+/// `d * 86_400_000 * 2^22` values pass, and only keys below 2^22 or far in the future fail.
+#[cfg(feature = "std")]
+pub fn looks_like_snowflake(id: i64, epoch: i64) -> bool {
+    let Ok(decomposed) = decompose_snowflake(id, epoch) else {
+        return false;
+    };
+    let Ok(clock) = SystemClock::new() else {
+        return false;
+    };
+    decomposed.timestamp > epoch && decomposed.timestamp <= clock.now_millis().saturating_add(DEFAULT_MAX_FUTURE_MS)
+}
+
+/// Describe an ID of the default layout over several lines, for pasting IDs into ops tooling.
+///
+/// Shows the decimal ID, its bits split into the sign, timestamp, worker ID and sequence
+/// fields, the timestamp as a UTC datetime, the worker ID and the sequence. IDs that
+/// cannot be decomposed are described by the reason instead.
+#[cfg(feature = "std")]
+pub fn inspect(id: i64, epoch: i64) -> String {
+    let decomposed = match decompose_snowflake(id, epoch) {
+        Ok(decomposed) => decomposed,
+        Err(e) => return format!("ID:        {}\nInvalid:   {}", id, e),
+    };
+    let bits = format!("{:064b}", id);
+    format!(
+        "ID:        {}\nBinary:    {} {} {} {}\nTimestamp: {} ({} ms)\nWorker ID: {}\nSequence:  {}",
+        id,
+        &bits[..1],
+        &bits[1..42],
+        &bits[42..52],
+        &bits[52..],
+        format_utc(decomposed.timestamp),
+        decomposed.timestamp,
+        decomposed.worker_id,
+        decomposed.sequence,
+    )
+}
+
+/// Format milliseconds since the Unix epoch as an ISO 8601 UTC datetime.
+#[cfg(feature = "std")]
+fn format_utc(timestamp_ms: i64) -> String {
+    let (days, ms_of_day) = (timestamp_ms.div_euclid(86_400_000), timestamp_ms.rem_euclid(86_400_000));
+    // Civil date from days since 1970-01-01, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        ms_of_day / 3_600_000,
+        ms_of_day / 60_000 % 60,
+        ms_of_day / 1_000 % 60,
+        ms_of_day % 1_000
+    )
+}
+
+/// The millisecond timestamp of midnight UTC at the start of the given date, usable as the
+/// `epoch` of the generators.
+///
+/// # Errors
+/// Returns `SnowflakeError::InvalidDate` if the date does not exist, e.g. February 30.
+#[cfg(feature = "chrono")]
+pub fn epoch_from_ymd(year: i32, month: u32, day: u32) -> Result<i64> {
+    chrono::NaiveDate::from_ymd_opt(year, month, day)
+        .map(|date| date.and_time(chrono::NaiveTime::MIN).and_utc().timestamp_millis())
+        .ok_or(SnowflakeError::InvalidDate)
+}
+
+/// The first millisecond timestamp that no longer fits a timestamp field of `ts_bits` bits
+/// counted from `epoch`, e.g. 41 for the default layout.
+///
+/// From then on generating fails with `SnowflakeError::TimestampOverflow`. Saturates at `i64::MAX`.
+pub const fn epoch_exhaustion_date(epoch: i64, ts_bits: usize) -> i64 {
+    if ts_bits >= 63 {
+        return i64::MAX;
+    }
+    epoch.saturating_add(1 << ts_bits)
+}
+
+/// The number of years left until [`epoch_exhaustion_date`], from the system time.
+/// Negative once the timestamp field is exhausted.
+///
+/// # Errors
+/// Returns `SnowflakeError::SystemTimeError` if the system time is before the Unix epoch.
+#[cfg(feature = "std")]
+pub fn epoch_years_remaining(epoch: i64, ts_bits: usize) -> Result<f64> {
+    const MS_PER_YEAR: f64 = 365.2425 * 86_400_000.0;
+    let now = SystemClock::new()?.now_millis();
+    Ok((epoch_exhaustion_date(epoch, ts_bits) - now) as f64 / MS_PER_YEAR)
+}
+
+/// The smallest ID of the default layout for the given millisecond, with worker ID and
+/// sequence set to 0.
+///
+/// Together with [`max_id_for_timestamp`], this allows time range queries on IDs, e.g.
+/// `WHERE id >= min AND id <= max`. The timestamp must not be before the epoch.
+pub fn min_id_for_timestamp(ts_ms: i64, epoch: i64) -> i64 {
+    (ts_ms - epoch) << DefaultRaw::TIMESTAMP_SHIFT
+}
+
+/// The largest ID of the default layout for the given millisecond, with all worker ID and
+/// sequence bits set.
+///
+/// The timestamp must not be before the epoch.
+pub fn max_id_for_timestamp(ts_ms: i64, epoch: i64) -> i64 {
+    min_id_for_timestamp(ts_ms, epoch) | ((1 << DefaultRaw::TIMESTAMP_SHIFT) - 1)
+}
+
+/// The SplitMix64 finalizer, mixing every bit of `seed` into every bit of the result.
+pub(crate) const fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+/// The shard (`0..num_shards`) an ID of the default layout is routed to.
+///
+/// The shard is a hash of the timestamp and sequence, ignoring the worker ID, so the IDs of
+/// a busy worker spread evenly over all shards instead of piling up on one. The result for
+/// a given ID and number of shards is stable across versions of this crate, so it can be
+/// used for routing persisted records.
+///
+/// # Panics
+/// Panics if `num_shards` is 0.
+pub const fn shard_of(id: i64, num_shards: u32) -> u32 {
+    assert!(num_shards > 0, "the number of shards must be positive");
+    let key = ((id as u64 >> DefaultRaw::TIMESTAMP_SHIFT) << SEQUENCE_BITS) | (id as u64 & DefaultRaw::SEQUENCE_MASK as u64);
+    (splitmix64(key) % num_shards as u64) as u32
+}
+
+/// Compare two IDs of the default layout by their timestamp only.
+///
+/// IDs generated in the same millisecond compare as equal, whatever their worker ID.
+pub fn compare_by_time(a: i64, b: i64) -> core::cmp::Ordering {
+    decompose_const(a, 0).timestamp.cmp(&decompose_const(b, 0).timestamp)
+}
+
+/// Compare two IDs of the default layout by their timestamp, then by their sequence number.
+///
+/// Unlike comparing the raw values, the worker ID is ignored.
+pub fn compare_by_time_then_sequence(a: i64, b: i64) -> core::cmp::Ordering {
+    let (a, b) = (decompose_const(a, 0), decompose_const(b, 0));
+    a.timestamp.cmp(&b.timestamp).then(a.sequence.cmp(&b.sequence))
+}
+
+/// Compare two IDs of the default layout generated with different epochs, by their absolute
+/// timestamp, then by their sequence number, e.g. to sort datasets merged from several systems.
+///
+/// Like [`compare_by_time_then_sequence`], the worker ID is ignored.
+pub fn compare_across_epochs(a: i64, epoch_a: i64, b: i64, epoch_b: i64) -> core::cmp::Ordering {
+    // Widened, as the absolute timestamp may not fit an i64 for extreme epochs.
+    let timestamp = |id: i64, epoch: i64| (id >> DefaultRaw::TIMESTAMP_SHIFT) as i128 + epoch as i128;
+    let (sequence_a, sequence_b) = (decompose_const(a, 0).sequence, decompose_const(b, 0).sequence);
+    timestamp(a, epoch_a).cmp(&timestamp(b, epoch_b)).then(sequence_a.cmp(&sequence_b))
+}
+
+/// Decompose an ID generated with `generate_id_u64` and the default layout, which has
+/// 42 timestamp bits instead of 41.
+///
+/// Every u64 is accepted. The epoch must be small enough for the timestamp not to overflow.
+#[must_use]
+pub const fn decompose_u64(id: u64, epoch: i64) -> SnowflakeDecomposed {
+    DefaultRaw::decompose_u64_with_epoch(id, epoch)
+}
+
+/// The number of low bits holding the checksum of IDs from `generate_id_with_checksum`.
+pub(crate) const CHECKSUM_BITS: usize = 2;
+
+/// Two parity bits over the bits above the checksum: the low one covers every other bit
+/// starting with the lowest, the high one the remaining bits.
+const fn checksum(id: i64) -> i64 {
+    let data = id >> CHECKSUM_BITS;
+    let even = (data & 0x5555_5555_5555_5555).count_ones() as i64 & 1;
+    let odd = (data & 0x2AAA_AAAA_AAAA_AAAA).count_ones() as i64 & 1;
+    even | (odd << 1)
+}
+
+pub(crate) const fn with_checksum(id: i64) -> i64 {
+    (id & !((1 << CHECKSUM_BITS) - 1)) | checksum(id)
+}
+
+/// Check the checksum of an ID generated with `generate_id_with_checksum`, with any layout.
+///
+/// Every single flipped bit is detected, as is any change to an odd number of bits at
+/// even or at odd positions. Other errors go unnoticed with a probability of 1 in 4.
+/// IDs generated without a checksum fail this check 3 times out of 4.
+pub const fn verify_checksum(id: i64) -> bool {
+    id >= 0 && id & ((1 << CHECKSUM_BITS) - 1) == checksum(id)
+}
+
+/// Decompose an ID generated with the default 41/10/12 layout in a `const` context.
+///
+/// The ID must be a valid, non-negative Snowflake ID. No validation is done, so the
+/// components of any other value are meaningless.
+#[must_use]
+pub const fn decompose_const(id: i64, epoch: i64) -> SnowflakeDecomposed {
+    DefaultRaw::decompose_with_epoch(id, epoch)
+}
+
+/// The components of a Snowflake ID.
+///
+/// Ordered by timestamp, then worker ID, then sequence, following the field order.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SnowflakeDecomposed {
     /// The timestamp component of the Snowflake ID in milliseconds since epoch.
     pub timestamp: i64,
@@ -78,22 +1279,272 @@ pub struct SnowflakeDecomposed {
     pub sequence: u16,
 }
 
+/// The difference between two decomposed IDs, returned by [`SnowflakeDecomposed::diff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SnowflakeDelta {
+    /// How many milliseconds the other ID was generated after this one, negative if before.
+    pub ms_delta: i64,
+    /// Whether both IDs have the same worker ID.
+    pub same_worker: bool,
+    /// The sequence of the other ID minus the sequence of this one.
+    pub sequence_delta: i64,
+}
+
+impl SnowflakeDecomposed {
+    /// The difference from this ID to `other`, e.g. to debug the ordering of two IDs.
+    pub fn diff(&self, other: &Self) -> SnowflakeDelta {
+        SnowflakeDelta {
+            ms_delta: other.timestamp.saturating_sub(self.timestamp),
+            same_worker: self.worker_id == other.worker_id,
+            sequence_delta: other.sequence as i64 - self.sequence as i64,
+        }
+    }
+
+    /// The datacenter ID, for IDs generated with the Twitter-style datacenter + worker split.
+    pub fn datacenter_id(&self) -> u8 {
+        (self.worker_id >> 5) as u8
+    }
+
+    /// The worker ID within the datacenter, for IDs generated with the Twitter-style split.
+    pub fn datacenter_worker_id(&self) -> u8 {
+        (self.worker_id & 0x1F) as u8
+    }
+
+    /// The timestamp as a `SystemTime`.
+    ///
+    /// Negative timestamps are subtracted from the Unix epoch. If the platform cannot
+    /// represent the resulting time, `UNIX_EPOCH` is returned.
+    #[cfg(feature = "std")]
+    pub fn system_time(&self) -> std::time::SystemTime {
+        let offset = std::time::Duration::from_millis(self.timestamp.unsigned_abs());
+        let time = if self.timestamp >= 0 {
+            std::time::UNIX_EPOCH.checked_add(offset)
+        } else {
+            std::time::UNIX_EPOCH.checked_sub(offset)
+        };
+        time.unwrap_or(std::time::UNIX_EPOCH)
+    }
+
+    /// The timestamp as a UTC datetime, with millisecond precision.
+    ///
+    /// Timestamps before the Unix epoch are supported. Timestamps outside of the range
+    /// chrono can represent are clamped to `DateTime::<Utc>::MIN_UTC` or `MAX_UTC`.
+    #[cfg(feature = "chrono")]
+    pub fn datetime(&self) -> chrono::DateTime<chrono::Utc> {
+        chrono::DateTime::from_timestamp_millis(self.timestamp).unwrap_or(if self.timestamp < 0 {
+            chrono::DateTime::<chrono::Utc>::MIN_UTC
+        } else {
+            chrono::DateTime::<chrono::Utc>::MAX_UTC
+        })
+    }
+}
 
 
-#[derive(Debug)]
+
+/// A block of IDs reserved at once, all from the same millisecond, e.g. by
+/// [`SnowflakeState::reserve`].
+///
+/// The IDs are consecutive, or evenly spaced with a sequence stride or
+/// `FieldOrder::SequenceThenWorker`, and increasing. Iterating yields them in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IdBlock {
+    first: i64,
+    len: u16,
+    step: i64,
+}
+
+impl IdBlock {
+    pub(crate) const fn new(first: i64, len: u16, step: i64) -> Self {
+        Self { first, len, step }
+    }
+
+    /// The first ID of the block, or 0 if the block is empty.
+    pub const fn first(&self) -> i64 {
+        self.first
+    }
+
+    /// The last ID of the block, or `None` if the block is empty.
+    pub const fn last(&self) -> Option<i64> {
+        match self.len {
+            0 => None,
+            len => Some(self.first + (len as i64 - 1) * self.step),
+        }
+    }
+
+    /// The number of IDs in the block.
+    pub const fn len(&self) -> u16 {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Check whether `id` belongs to the block.
+    pub const fn contains(&self, id: i64) -> bool {
+        match self.last() {
+            Some(last) => id >= self.first && id <= last && (id - self.first) % self.step == 0,
+            None => false,
+        }
+    }
+
+    /// Iterate over the IDs of the block, in increasing order.
+    pub fn iter(&self) -> core::iter::StepBy<core::ops::Range<i64>> {
+        (self.first..self.first + self.len as i64 * self.step).step_by(self.step as usize)
+    }
+}
+
+impl IntoIterator for IdBlock {
+    type Item = i64;
+    type IntoIter = core::iter::StepBy<core::ops::Range<i64>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for &IdBlock {
+    type Item = i64;
+    type IntoIter = core::iter::StepBy<core::ops::Range<i64>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SnowflakeError {
     /// Error when the worker_id is out of range (0-1023).
     WorkerIdOutOfRange,
+    /// Error when all sequence numbers for the current millisecond have been used.
+    SequenceExhausted,
+    /// Error when the clock reports a time earlier than the last generated ID.
+    ClockMovedBackwards { by_ms: i64 },
+    /// Error when the datacenter_id is out of range (0-31).
+    DatacenterIdOutOfRange,
+    /// Error when the epoch is set in the future relative to the current time.
+    EpochInFuture,
+    /// Error when the system time is before the Unix epoch.
+    SystemTimeError,
+    /// Error when the machine identity used to derive a worker ID is unavailable.
+    MachineIdUnavailable,
+    /// Error when an ID has its sign bit set and cannot be a Snowflake ID.
+    NegativeId,
+    /// Error when an ID was generated by a different worker.
+    WorkerIdMismatch { expected: u16, found: u16 },
+    /// Error when the thread-local generator is used before being configured.
+    NotConfigured,
+    /// Error when the thread-local or global generator is configured more than once.
+    AlreadyConfigured,
+    /// Error when every worker ID has already been handed out to a thread.
+    WorkerIdsExhausted,
+    /// Error when a backfill timestamp is before the epoch.
+    TimestampBeforeEpoch,
+    /// Error when a backfill timestamp is not before the last generated ID.
+    TimestampNotInPast,
+    /// Error when the worker_id is the value reserved for unconfigured nodes.
+    ReservedWorkerId,
+    /// Error when a timestamp no longer fits once the epoch is added.
+    TimestampOverflow,
+    /// Error when an initial sequence number does not fit the layout.
+    SequenceOutOfRange,
+    /// Error when a live generator in this process already uses the worker_id.
+    DuplicateWorkerId,
+    /// Error when a sequence stride does not evenly divide the sequence space.
+    InvalidSequenceStride,
+    /// Error when restoring a state from bytes that are not a valid snapshot.
+    InvalidSnapshot,
+    /// Error when the environment variable holding the worker ID is not set.
+    EnvVarMissing,
+    /// Error when the environment variable holding the worker ID is not a valid number.
+    EnvVarInvalid,
+    /// Error when a tenant layout has no tenant bits or more than the worker bits.
+    InvalidTenantLayout,
+    /// Error when a tenant ID does not fit the tenant bits of its layout.
+    TenantIdOutOfRange,
+    /// Error when a validated ID has a timestamp too far ahead of the current time.
+    TimestampInFuture,
+    /// Error when the generator was shut down or its owner task has stopped.
+    GeneratorClosed,
+    /// Error when a calendar date does not exist.
+    InvalidDate,
+    /// Error when an operation needs the sequence in the lowest bits of the ID.
+    UnsupportedFieldOrder,
+    /// Error when a block of IDs exceeds the sequence of a whole millisecond.
+    BlockTooLarge,
+    /// Error when a pod name does not end in a `-N` ordinal.
+    MissingPodOrdinal,
+    /// Error when the global generator is used before being initialized.
+    NotInitialized,
+}
+
+impl core::fmt::Display for SnowflakeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SnowflakeError::WorkerIdOutOfRange => write!(f, "Worker ID is out of range (0-{})", MAX_WORKER_ID),
+            SnowflakeError::SequenceExhausted => write!(f, "Sequence exhausted for the current millisecond"),
+            SnowflakeError::ClockMovedBackwards { by_ms } => write!(f, "Clock moved backwards by {} ms", by_ms),
+            SnowflakeError::DatacenterIdOutOfRange => write!(f, "Datacenter ID is out of range (0-31)"),
+            SnowflakeError::EpochInFuture => write!(f, "Epoch is in the future"),
+            SnowflakeError::SystemTimeError => write!(f, "System time is before the Unix epoch"),
+            SnowflakeError::MachineIdUnavailable => write!(f, "Machine identity is unavailable"),
+            SnowflakeError::NegativeId => write!(f, "ID is negative"),
+            SnowflakeError::WorkerIdMismatch { expected, found } => {
+                write!(f, "ID was generated by worker {} instead of worker {}", found, expected)
+            }
+            SnowflakeError::NotConfigured => write!(f, "Generator is not configured"),
+            SnowflakeError::AlreadyConfigured => write!(f, "Generator is already configured"),
+            SnowflakeError::WorkerIdsExhausted => write!(f, "All worker IDs (0-{}) are in use", MAX_WORKER_ID),
+            SnowflakeError::TimestampBeforeEpoch => write!(f, "Timestamp is before the epoch"),
+            SnowflakeError::TimestampNotInPast => write!(f, "Timestamp is not in the past"),
+            SnowflakeError::ReservedWorkerId => write!(f, "Worker ID is reserved for unconfigured nodes"),
+            SnowflakeError::TimestampOverflow => write!(f, "Timestamp overflows once the epoch is added"),
+            SnowflakeError::SequenceOutOfRange => write!(f, "Sequence is out of range (0-{})", MAX_SEQUENCE),
+            SnowflakeError::DuplicateWorkerId => write!(f, "Worker ID is already used by a live generator"),
+            SnowflakeError::InvalidSequenceStride => write!(f, "Sequence step must divide the sequence space and exceed the offset"),
+            SnowflakeError::InvalidSnapshot => write!(f, "Invalid generator snapshot"),
+            SnowflakeError::EnvVarMissing => write!(f, "Worker ID environment variable is not set"),
+            SnowflakeError::EnvVarInvalid => write!(f, "Worker ID environment variable is not a valid number"),
+            SnowflakeError::InvalidTenantLayout => write!(f, "Tenant bits must be between 1 and the worker bits"),
+            SnowflakeError::TenantIdOutOfRange => write!(f, "Tenant ID does not fit the tenant bits"),
+            SnowflakeError::TimestampInFuture => write!(f, "Timestamp is too far in the future"),
+            SnowflakeError::GeneratorClosed => write!(f, "Generator is closed"),
+            SnowflakeError::InvalidDate => write!(f, "Date does not exist"),
+            SnowflakeError::UnsupportedFieldOrder => write!(f, "Operation needs the sequence in the lowest bits"),
+            SnowflakeError::BlockTooLarge => write!(f, "Block of IDs exceeds the sequence of one millisecond"),
+            SnowflakeError::MissingPodOrdinal => write!(f, "Pod name does not end in an ordinal"),
+            SnowflakeError::NotInitialized => write!(f, "Global generator is not initialized"),
+        }
+    }
+}
+
+impl core::error::Error for SnowflakeError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnowflakeDecodeError {
+    /// Error when a Base62 string is empty, contains invalid characters, has leading
+    /// zeros or does not fit in a non-negative i64.
+    InvalidBase62,
+    /// Error when a Crockford Base32 string is empty, contains invalid characters or
+    /// does not fit in a non-negative i64.
+    InvalidCrockford,
+    /// Error when a string is not a valid decimal i64.
+    InvalidDecimal,
+    /// Error when a parsed ID is negative.
+    NegativeId,
 }
 
-impl std::fmt::Display for SnowflakeError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for SnowflakeDecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
-            SnowflakeError::WorkerIdOutOfRange => write!(f, "Worker ID is out of range (0-1023)")
+            SnowflakeDecodeError::InvalidBase62 => write!(f, "Invalid Base62 snowflake ID"),
+            SnowflakeDecodeError::InvalidCrockford => write!(f, "Invalid Crockford Base32 snowflake ID"),
+            SnowflakeDecodeError::InvalidDecimal => write!(f, "Invalid decimal snowflake ID"),
+            SnowflakeDecodeError::NegativeId => write!(f, "Snowflake IDs cannot be negative"),
         }
     }
 }
 
-impl std::error::Error for SnowflakeError {}
+impl core::error::Error for SnowflakeDecodeError {}
 
-pub type Result<T> = std::result::Result<T, SnowflakeError>;
\ No newline at end of file
+pub type Result<T> = core::result::Result<T, SnowflakeError>;
\ No newline at end of file
@@ -0,0 +1,129 @@
+use crate::common::SnowflakeDecodeError;
+
+const BASE62_ALPHABET: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+const CROCKFORD_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+pub type Result<T> = std::result::Result<T, SnowflakeDecodeError>;
+
+/// Encode a Snowflake ID as a Base62 string (`0-9A-Za-z`) without leading zeros.
+///
+/// # Panics
+/// Panics if the id is negative, which is never the case for generated IDs.
+pub fn encode_base62(id: i64) -> String {
+    assert!(id >= 0, "Snowflake IDs are non-negative");
+    if id == 0 {
+        return "0".to_string();
+    }
+    let mut value = id as u64;
+    let mut buf = Vec::with_capacity(11);
+    while value > 0 {
+        buf.push(BASE62_ALPHABET[(value % 62) as usize]);
+        value /= 62;
+    }
+    buf.reverse();
+    String::from_utf8(buf).expect("Base62 alphabet is ASCII")
+}
+
+/// Decode a Base62 string produced by [`encode_base62`].
+///
+/// Only the canonical form is accepted: `"0"` for zero and no leading zeros otherwise,
+/// so every ID has exactly one valid encoding.
+///
+/// # Errors
+/// Returns `SnowflakeDecodeError::InvalidBase62` if the string is not a valid encoding
+/// of a non-negative i64.
+pub fn decode_base62(s: &str) -> Result<i64> {
+    let bytes = s.as_bytes();
+    if bytes.is_empty() || (bytes.len() > 1 && bytes[0] == b'0') {
+        return Err(SnowflakeDecodeError::InvalidBase62);
+    }
+    let mut value: i64 = 0;
+    for &c in bytes {
+        let digit = match c {
+            b'0'..=b'9' => c - b'0',
+            b'A'..=b'Z' => c - b'A' + 10,
+            b'a'..=b'z' => c - b'a' + 36,
+            _ => return Err(SnowflakeDecodeError::InvalidBase62),
+        };
+        value = value
+            .checked_mul(62)
+            .and_then(|v| v.checked_add(digit as i64))
+            .ok_or(SnowflakeDecodeError::InvalidBase62)?;
+    }
+    Ok(value)
+}
+
+/// Encode a Snowflake ID as an upper-case Crockford Base32 string without leading zeros.
+///
+/// # Panics
+/// Panics if the id is negative, which is never the case for generated IDs.
+pub fn encode_crockford(id: i64) -> String {
+    assert!(id >= 0, "Snowflake IDs are non-negative");
+    if id == 0 {
+        return "0".to_string();
+    }
+    let mut value = id as u64;
+    let mut buf = Vec::with_capacity(13);
+    while value > 0 {
+        buf.push(CROCKFORD_ALPHABET[(value & 0x1F) as usize]);
+        value >>= 5;
+    }
+    buf.reverse();
+    String::from_utf8(buf).expect("Crockford alphabet is ASCII")
+}
+
+/// Decode a Crockford Base32 string, such as one produced by [`encode_crockford`].
+///
+/// Decoding is case-insensitive and follows the Crockford substitutions, reading `I` and
+/// `L` as `1` and `O` as `0`. No check symbol is expected.
+///
+/// # Errors
+/// Returns `SnowflakeDecodeError::InvalidCrockford` if the string is empty, contains
+/// characters outside the alphabet or does not fit in a non-negative i64.
+pub fn decode_crockford(s: &str) -> Result<i64> {
+    if s.is_empty() {
+        return Err(SnowflakeDecodeError::InvalidCrockford);
+    }
+    let mut value: i64 = 0;
+    for c in s.bytes() {
+        let digit = match c.to_ascii_uppercase() {
+            b'O' => 0,
+            b'I' | b'L' => 1,
+            c => CROCKFORD_ALPHABET
+                .iter()
+                .position(|&a| a == c)
+                .ok_or(SnowflakeDecodeError::InvalidCrockford)?,
+        };
+        value = value
+            .checked_mul(32)
+            .and_then(|v| v.checked_add(digit as i64))
+            .ok_or(SnowflakeDecodeError::InvalidCrockford)?;
+    }
+    Ok(value)
+}
+
+/// The number of digits of `i64::MAX`, the width of [`encode_padded_decimal`].
+pub const PADDED_DECIMAL_LEN: usize = 19;
+
+/// Encode a Snowflake ID as a decimal string zero-padded to 19 digits, so that sorting the
+/// strings sorts the IDs, e.g. for stores that only sort strings.
+///
+/// # Panics
+/// Panics if the id is negative, which is never the case for generated IDs.
+pub fn encode_padded_decimal(id: i64) -> String {
+    assert!(id >= 0, "Snowflake IDs are non-negative");
+    format!("{:0width$}", id, width = PADDED_DECIMAL_LEN)
+}
+
+/// Decode a decimal string, such as one produced by [`encode_padded_decimal`], stripping
+/// any leading zeros.
+///
+/// # Errors
+/// Returns `SnowflakeDecodeError::InvalidDecimal` if the string is empty, contains
+/// characters other than digits or does not fit in a non-negative i64.
+pub fn decode_padded_decimal(s: &str) -> Result<i64> {
+    if s.is_empty() || !s.bytes().all(|c| c.is_ascii_digit()) {
+        return Err(SnowflakeDecodeError::InvalidDecimal);
+    }
+    s.parse().map_err(|_| SnowflakeDecodeError::InvalidDecimal)
+}
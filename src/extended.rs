@@ -0,0 +1,226 @@
+//! 128-bit Snowflake IDs with room for a practically unlimited number of IDs per millisecond.
+//!
+//! Layout, from most-significant bit to least:
+//!
+//! - 64 bits: timestamp in milliseconds since the custom epoch
+//! - 16 bits: worker ID (0..65535)
+//! - 48 bits: per-millisecond sequence
+//!
+//! The IDs sort by time when compared as `u128`, or bytewise in big-endian order, which is
+//! how UUIDs are compared. The value is not an RFC 4122 UUID: the version and variant bits
+//! are not set.
+
+use crate::common::{ClockSource, Result, SnowflakeError, SystemClock};
+
+const SEQUENCE_BITS: u32 = 48;
+const WORKER_BITS: u32 = 16;
+const SEQUENCE_MASK: u64 = (1 << SEQUENCE_BITS) - 1;
+
+/// A 128-bit Snowflake ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ExtendedId(u128);
+
+impl ExtendedId {
+    /// Wrap a raw ID.
+    #[must_use]
+    pub const fn new(id: u128) -> Self {
+        Self(id)
+    }
+
+    /// The raw `u128` value of the ID.
+    pub const fn as_u128(&self) -> u128 {
+        self.0
+    }
+
+    /// Decompose the ID into its components.
+    ///
+    /// # Arguments
+    /// * `epoch` - The custom epoch timestamp in milliseconds the ID was generated with.
+    #[must_use]
+    pub fn decompose(&self, epoch: i64) -> ExtendedDecomposed {
+        decompose_extended(self.0, epoch)
+    }
+
+    /// The ID as a `uuid::Uuid` with the same 128 bits.
+    #[cfg(feature = "uuid")]
+    pub fn to_uuid(&self) -> uuid::Uuid {
+        uuid::Uuid::from_u128(self.0)
+    }
+
+    /// The ID stored in a `uuid::Uuid` by [`Self::to_uuid`].
+    #[cfg(feature = "uuid")]
+    pub fn from_uuid(uuid: uuid::Uuid) -> Self {
+        Self(uuid.as_u128())
+    }
+}
+
+impl From<ExtendedId> for u128 {
+    fn from(id: ExtendedId) -> Self {
+        id.0
+    }
+}
+
+/// The components of a 128-bit Snowflake ID, returned by [`decompose_extended`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ExtendedDecomposed {
+    /// The timestamp component of the ID in milliseconds since the Unix epoch.
+    pub timestamp: i64,
+    /// The worker ID component of the ID.
+    pub worker_id: u16,
+    /// The sequence number component of the ID.
+    pub sequence: u64,
+}
+
+/// Decompose a 128-bit ID into its components.
+#[must_use]
+pub fn decompose_extended(id: u128, epoch: i64) -> ExtendedDecomposed {
+    ExtendedDecomposed {
+        timestamp: ((id >> (WORKER_BITS + SEQUENCE_BITS)) as u64 as i64).wrapping_add(epoch),
+        worker_id: (id >> SEQUENCE_BITS) as u16,
+        sequence: id as u64 & SEQUENCE_MASK,
+    }
+}
+
+struct ExtendedState<C> {
+    time_since_epoch: u64,
+    worker_id: u16,
+    sequence: u64,
+    epoch: i64,
+    clock: C,
+    #[cfg(feature = "dedup-check")]
+    _worker_id_guard: crate::registry::WorkerIdGuard,
+}
+
+impl<C: ClockSource> ExtendedState<C> {
+    fn generate_id(&mut self) -> u128 {
+        loop {
+            let current_time = (self.clock.now_millis() - self.epoch).max(0) as u64;
+            if current_time > self.time_since_epoch {
+                self.time_since_epoch = current_time;
+                self.sequence = 0;
+            } else if self.sequence > SEQUENCE_MASK {
+                // Only reachable after 2^48 IDs within the same millisecond.
+                std::thread::yield_now();
+                continue;
+            }
+            // When the clock moves backwards, keep counting in the last millisecond.
+            let id = ((self.time_since_epoch as u128) << (WORKER_BITS + SEQUENCE_BITS))
+                | ((self.worker_id as u128) << SEQUENCE_BITS)
+                | self.sequence as u128;
+            self.sequence += 1;
+            return id;
+        }
+    }
+}
+
+/// A thread-safe generator of 128-bit Snowflake IDs.
+#[must_use]
+pub struct SnowflakeGenerator<C: ClockSource = SystemClock> {
+    inner: std::sync::Arc<std::sync::Mutex<ExtendedState<C>>>,
+}
+
+impl SnowflakeGenerator {
+    /// Create a new 128-bit Snowflake ID generator.
+    ///
+    /// # Arguments
+    /// * `epoch` - The custom epoch timestamp in milliseconds.
+    /// * `worker_id` - The worker ID (0-65535).
+    /// # Errors
+    /// Returns `SnowflakeError::EpochInFuture` if the epoch is set in the future relative to the current system time.
+    /// Returns `SnowflakeError::SystemTimeError` if the system time is before the Unix epoch.
+    pub fn new(epoch: i64, worker_id: u16) -> Result<Self> {
+        Self::with_clock(epoch, worker_id, SystemClock::new()?)
+    }
+
+    /// Create a generator with epoch 0 and worker ID 0, for tests and examples.
+    ///
+    /// # Panics
+    /// Panics if the system time is before the Unix epoch.
+    /// With the `dedup-check` feature, panics if another live 128-bit generator uses worker ID 0.
+    pub fn quick() -> Self {
+        Self::new(0, 0).expect("epoch 0 and worker ID 0 are valid")
+    }
+}
+
+impl<C: ClockSource> SnowflakeGenerator<C> {
+    /// Create a new 128-bit Snowflake ID generator driven by a custom clock.
+    ///
+    /// # Arguments
+    /// * `epoch` - The custom epoch timestamp in milliseconds.
+    /// * `worker_id` - The worker ID (0-65535).
+    /// * `clock` - The clock used to read the current time.
+    /// # Errors
+    /// Returns `SnowflakeError::EpochInFuture` if the epoch is set in the future relative to the clock's current time.
+    /// Returns `SnowflakeError::DuplicateWorkerId` with the `dedup-check` feature if another
+    /// live 128-bit generator already uses the worker_id in this process.
+    pub fn with_clock(epoch: i64, worker_id: u16, clock: C) -> Result<Self> {
+        let now = clock.now_millis();
+        if now < epoch {
+            return Err(SnowflakeError::EpochInFuture);
+        }
+        Ok(Self {
+            inner: std::sync::Arc::new(std::sync::Mutex::new(ExtendedState {
+                time_since_epoch: (now - epoch) as u64,
+                worker_id,
+                sequence: 0,
+                epoch,
+                clock,
+                #[cfg(feature = "dedup-check")]
+                _worker_id_guard: crate::registry::WorkerIdGuard::acquire_extended(worker_id)?,
+            })),
+        })
+    }
+
+    /// Generate a new 128-bit Snowflake ID.
+    ///
+    /// # Panics
+    /// Panics if the internal Mutex is poisoned.
+    #[must_use]
+    pub fn generate_id(&self) -> u128 {
+        let mut guard = self.inner.lock();
+        match guard {
+            Ok(ref mut g) => g.generate_id(),
+            Err(e) => {
+                panic!("Mutex poisoned: {}", e);
+            },
+        }
+    }
+
+    /// Generate a new strongly-typed 128-bit Snowflake ID.
+    ///
+    /// # Panics
+    /// Panics if the internal Mutex is poisoned.
+    #[must_use]
+    pub fn generate(&self) -> ExtendedId {
+        ExtendedId::new(self.generate_id())
+    }
+
+    /// Decompose a 128-bit Snowflake ID into its components.
+    ///
+    /// # Panics
+    /// Panics if the internal Mutex is poisoned.
+    #[must_use]
+    pub fn decompose(&self, id: u128) -> ExtendedDecomposed {
+        let guard = self.inner.lock();
+        match guard {
+            Ok(ref g) => decompose_extended(id, g.epoch),
+            Err(e) => {
+                panic!("Mutex poisoned: {}", e);
+            },
+        }
+    }
+}
+
+impl<C: ClockSource> Clone for SnowflakeGenerator<C> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Default for SnowflakeGenerator {
+    fn default() -> Self {
+        Self::quick()
+    }
+}
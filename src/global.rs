@@ -0,0 +1,58 @@
+//! A process-wide generator, initialized once with [`init`] and usable from anywhere
+//! without passing a generator around.
+//!
+//! This is a thin wrapper over a shared [`multi_thread::sync_generator::SnowflakeGenerator`](crate::multi_thread::sync_generator::SnowflakeGenerator).
+
+use crate::common::{Result, SnowflakeError};
+use crate::multi_thread::sync_generator::SnowflakeGenerator;
+use std::sync::OnceLock;
+
+static GENERATOR: OnceLock<SnowflakeGenerator> = OnceLock::new();
+
+/// Initialize the global generator.
+///
+/// # Arguments
+/// * `epoch` - The custom epoch timestamp in milliseconds.
+/// * `worker_id` - The worker ID (0-1023).
+/// # Errors
+/// Returns `SnowflakeError::AlreadyConfigured` if `init` was already called successfully.
+/// Otherwise fails like `SnowflakeGenerator::new`.
+pub fn init(epoch: i64, worker_id: u16) -> Result<()> {
+    if GENERATOR.get().is_some() {
+        return Err(SnowflakeError::AlreadyConfigured);
+    }
+    GENERATOR
+        .set(SnowflakeGenerator::new(epoch, worker_id)?)
+        .map_err(|_| SnowflakeError::AlreadyConfigured)
+}
+
+/// The global generator, e.g. to call any of its other methods.
+///
+/// # Errors
+/// Returns `SnowflakeError::NotInitialized` if [`init`] has not been called.
+pub fn generator() -> Result<&'static SnowflakeGenerator> {
+    GENERATOR.get().ok_or(SnowflakeError::NotInitialized)
+}
+
+/// Generate a new Snowflake ID with the global generator.
+///
+/// # Panics
+/// Panics if [`init`] has not been called.
+#[must_use]
+pub fn generate_id() -> i64 {
+    match try_generate_id() {
+        Ok(id) => id,
+        Err(e) => panic!("Global generator unavailable: {}", e),
+    }
+}
+
+/// Generate a new Snowflake ID with the global generator, failing if it is not initialized.
+///
+/// Unlike `SnowflakeGenerator::try_generate_id`, this still waits for the next millisecond
+/// when the sequence is exhausted.
+///
+/// # Errors
+/// Returns `SnowflakeError::NotInitialized` if [`init`] has not been called.
+pub fn try_generate_id() -> Result<i64> {
+    Ok(generator()?.generate_id())
+}
@@ -0,0 +1,107 @@
+use crate::common::{decompose_snowflake, SnowflakeDecodeError, SnowflakeDecomposed, SnowflakeError};
+
+/// A strongly-typed Snowflake ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SnowflakeId(i64);
+
+impl SnowflakeId {
+    /// Wrap a raw ID.
+    #[must_use]
+    pub const fn new(id: i64) -> Self {
+        Self(id)
+    }
+
+    /// The raw `i64` value of the ID.
+    pub const fn as_i64(&self) -> i64 {
+        self.0
+    }
+
+    /// Decompose the ID into its components, assuming the default 41/10/12 layout.
+    ///
+    /// # Arguments
+    /// * `epoch` - The custom epoch timestamp in milliseconds the ID was generated with.
+    /// # Errors
+    /// Returns `SnowflakeError::NegativeId` if the sign bit is set.
+    /// Returns `SnowflakeError::TimestampOverflow` if adding the epoch to the timestamp overflows.
+    pub fn decompose(&self, epoch: i64) -> crate::common::Result<SnowflakeDecomposed> {
+        decompose_snowflake(self.0, epoch)
+    }
+}
+
+impl From<SnowflakeId> for i64 {
+    fn from(id: SnowflakeId) -> Self {
+        id.0
+    }
+}
+
+impl core::fmt::Display for SnowflakeId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl TryFrom<i64> for SnowflakeId {
+    type Error = SnowflakeError;
+
+    /// # Errors
+    /// Returns `SnowflakeError::NegativeId` if the sign bit is set.
+    fn try_from(id: i64) -> Result<Self, Self::Error> {
+        if id < 0 {
+            return Err(SnowflakeError::NegativeId);
+        }
+        Ok(Self(id))
+    }
+}
+
+impl core::str::FromStr for SnowflakeId {
+    type Err = SnowflakeDecodeError;
+
+    /// Parse the decimal form written by `Display`.
+    ///
+    /// # Errors
+    /// Returns `SnowflakeDecodeError::InvalidDecimal` if the string is not a decimal i64.
+    /// Returns `SnowflakeDecodeError::NegativeId` if the value is negative.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let id: i64 = s.parse().map_err(|_| SnowflakeDecodeError::InvalidDecimal)?;
+        Self::try_from(id).map_err(|_| SnowflakeDecodeError::NegativeId)
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl<DB: sqlx::Database> sqlx::Type<DB> for SnowflakeId
+where
+    i64: sqlx::Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        <i64 as sqlx::Type<DB>>::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        <i64 as sqlx::Type<DB>>::compatible(ty)
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl<'q, DB: sqlx::Database> sqlx::Encode<'q, DB> for SnowflakeId
+where
+    i64: sqlx::Encode<'q, DB>,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as sqlx::Database>::ArgumentBuffer,
+    ) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
+        <i64 as sqlx::Encode<'q, DB>>::encode_by_ref(&self.0, buf)
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl<'r, DB: sqlx::Database> sqlx::Decode<'r, DB> for SnowflakeId
+where
+    i64: sqlx::Decode<'r, DB>,
+{
+    /// Decode a `BIGINT` column, rejecting negative values with `SnowflakeError::NegativeId`.
+    fn decode(value: <DB as sqlx::Database>::ValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        let id = <i64 as sqlx::Decode<'r, DB>>::decode(value)?;
+        Ok(Self::try_from(id)?)
+    }
+}
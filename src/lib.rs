@@ -1,7 +1,50 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "std")]
+pub mod builder;
+#[cfg(feature = "std")]
+pub mod encoding;
+#[cfg(feature = "std")]
+pub mod extended;
+#[cfg(feature = "global")]
+pub mod global;
+pub mod id;
+#[cfg(feature = "std")]
 pub mod multi_thread;
+pub mod raw;
+#[cfg(feature = "dedup-check")]
+mod registry;
+#[cfg(feature = "std")]
 pub mod single_thread;
+pub mod tenant;
+#[cfg(feature = "std")]
+pub mod sonyflake;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+#[cfg(feature = "std")]
+pub mod thread_local_generator;
+#[cfg(feature = "std")]
+pub mod worker_id;
 
 mod common;
 
-#[cfg(test)]
-pub mod tests;
\ No newline at end of file
+pub use common::{
+    compare_across_epochs, compare_by_time, compare_by_time_then_sequence, compose_snowflake, decompose_const,
+    decompose_discord, decompose_snowflake, decompose_u64, discord_epoch, epoch_exhaustion_date, max_id_for_timestamp,
+    min_id_for_timestamp, pack_datacenter, shard_of, timestamp_of, verify_checksum, AsyncSnowflakeSource, ClockSource,
+    IdBlock, Result, SnowflakeDecodeError, SnowflakeDecomposed, SnowflakeDelta, SnowflakeError, SnowflakeSource,
+    MAX_SEQUENCE, MAX_WORKER_ID, SEQUENCE_BITS, TIMESTAMP_BITS, WORKER_BITS,
+};
+#[cfg(feature = "std")]
+pub use common::{
+    epoch_years_remaining, inspect, looks_like_snowflake, validate_id, validate_id_with_tolerance, BackoffStrategy,
+    ClockMode, DefaultSnowflake, ForwardJumpPolicy, MicrosClock, MicrosSnowflake, SnowflakeState, SnowflakeStats,
+    SystemClock, DEFAULT_MAX_FUTURE_MS, DEFAULT_RESYNC_THRESHOLD_MS, SNAPSHOT_LEN,
+};
+#[cfg(feature = "chrono")]
+pub use common::epoch_from_ymd;
+#[cfg(feature = "std")]
+pub use builder::SnowflakeBuilder;
+
+#[cfg(all(test, feature = "std"))]
+pub mod tests;
@@ -1,10 +1,13 @@
+#[cfg(feature = "tokio")]
 pub mod async_generator {
-    use crate::common::SnowflakeState as Snowflake;
-    use crate::common::Result;
+    use crate::common::DefaultSnowflake as Snowflake;
+    use crate::common::{ClockSource, Result, SnowflakeError, SystemClock};
+    use crate::id::SnowflakeId;
 
     /// An asynchronous Snowflake ID generator using Tokio's Mutex for thread safety.
-    pub struct SnowflakeGenerator {
-        inner: std::sync::Arc<tokio::sync::Mutex<Snowflake>>,
+    #[must_use]
+    pub struct SnowflakeGenerator<C: ClockSource = SystemClock> {
+        inner: std::sync::Arc<tokio::sync::Mutex<Snowflake<C>>>,
     }
 
     impl SnowflakeGenerator {
@@ -16,93 +19,1690 @@ pub mod async_generator {
         /// * `worker_id` - The worker ID (0-1023).
         /// # Errors
         /// Returns `SnowflakeError::WorkerIdOutOfRange` if the worker_id is out of range.
-        /// 
-        /// # Panics
-        /// Panics if the epoch is set in the future relative to the current system time.
+        /// Returns `SnowflakeError::EpochInFuture` if the epoch is set in the future relative to the current system time.
+        /// Returns `SnowflakeError::SystemTimeError` if the system time is before the Unix epoch.
         pub fn new(epoch: i64, worker_id: u16) -> Result<Self> {
             Ok(Self {
                 inner: std::sync::Arc::new(tokio::sync::Mutex::new(Snowflake::new(epoch, worker_id)?)),
             })
         }
 
+        /// Create a generator with epoch 0 and worker ID 0, for tests and examples.
+        /// 
+        /// # Panics
+        /// Panics if the system time is before the Unix epoch.
+        /// With the `dedup-check` feature, panics if another live generator uses worker ID 0.
+        pub fn quick() -> Self {
+            Self::new(0, 0).expect("epoch 0 and worker ID 0 are valid")
+        }
+
+        /// Create a new asynchronous Snowflake ID generator using the Twitter-style
+        /// split of the worker bits into a datacenter ID and a worker ID.
+        /// 
+        /// # Arguments
+        /// * `epoch` - The custom epoch timestamp in milliseconds.
+        /// * `datacenter_id` - The datacenter ID (0-31).
+        /// * `worker_id` - The worker ID within the datacenter (0-31).
+        /// # Errors
+        /// Returns `SnowflakeError::DatacenterIdOutOfRange` or `SnowflakeError::WorkerIdOutOfRange`
+        /// if either ID is out of range.
+        /// Returns `SnowflakeError::EpochInFuture` if the epoch is set in the future relative to the current system time.
+        /// Returns `SnowflakeError::SystemTimeError` if the system time is before the Unix epoch.
+        pub fn with_datacenter(epoch: i64, datacenter_id: u8, worker_id: u8) -> Result<Self> {
+            Ok(Self {
+                inner: std::sync::Arc::new(tokio::sync::Mutex::new(Snowflake::with_datacenter(epoch, datacenter_id, worker_id)?)),
+            })
+        }
+    }
+
+    impl<C: ClockSource> SnowflakeGenerator<C> {
+        /// Create a new asynchronous Snowflake ID generator driven by a custom clock.
+        /// 
+        /// # Arguments
+        /// * `epoch` - The custom epoch timestamp in milliseconds.
+        /// * `worker_id` - The worker ID (0-1023).
+        /// * `clock` - The clock used to read the current time.
+        /// # Errors
+        /// Returns `SnowflakeError::WorkerIdOutOfRange` if the worker_id is out of range.
+        /// Returns `SnowflakeError::EpochInFuture` if the epoch is set in the future relative to the clock's current time.
+        pub fn with_clock(epoch: i64, worker_id: u16, clock: C) -> Result<Self> {
+            Ok(Self {
+                inner: std::sync::Arc::new(tokio::sync::Mutex::new(Snowflake::with_clock(epoch, worker_id, clock)?)),
+            })
+        }
+
+        pub(crate) fn from_state(state: Snowflake<C>) -> Self {
+            Self {
+                inner: std::sync::Arc::new(tokio::sync::Mutex::new(state)),
+            }
+        }
+
         /// Asynchronously generate a new Snowflake ID.
+        #[must_use]
         pub async fn generate_id(&self) -> i64 {
             let mut guard = self.inner.lock().await;
             guard.generate_id()
         }
 
+        /// Asynchronously generate `n` monotonic Snowflake IDs, locking once per millisecond
+        /// instead of once per ID.
+        ///
+        /// When the 4096 IDs of a millisecond are used up (or the clock moves backwards),
+        /// the lock is released before sleeping until the next millisecond, so other callers
+        /// are not blocked. IDs from concurrent callers may therefore interleave with the batch,
+        /// but the returned IDs are always strictly increasing.
+        #[must_use]
+        pub async fn generate_ids(&self, n: usize) -> Vec<i64> {
+            let mut ids = Vec::with_capacity(n);
+            while ids.len() < n {
+                let wait_ms = {
+                    let mut guard = self.inner.lock().await;
+                    loop {
+                        if ids.len() == n {
+                            break 0;
+                        }
+                        match guard.try_generate_id() {
+                            Ok(id) => ids.push(id),
+                            Err(SnowflakeError::ClockMovedBackwards { by_ms }) => break by_ms as u64,
+                            Err(e @ SnowflakeError::TimestampOverflow) => panic!("Cannot generate an ID: {}", e),
+                            Err(_) => break 1,
+                        }
+                    }
+                };
+                if wait_ms > 0 {
+                    tokio::time::sleep(std::time::Duration::from_millis(wait_ms)).await;
+                }
+            }
+            ids
+        }
+
+        /// Asynchronously generate a new strongly-typed Snowflake ID.
+        #[must_use]
+        pub async fn generate(&self) -> SnowflakeId {
+            SnowflakeId::new(self.generate_id().await)
+        }
+
+        /// Asynchronously generate a new Snowflake ID together with its components, taking
+        /// the lock only once.
+        #[must_use]
+        pub async fn generate_with_decomposed(&self) -> (i64, crate::common::SnowflakeDecomposed) {
+            let mut guard = self.inner.lock().await;
+            let id = guard.generate_id();
+            (id, guard.decompose(id))
+        }
+
+        /// Asynchronously generate a new Snowflake ID formatted as a decimal string.
+        #[must_use]
+        pub async fn generate_id_string(&self) -> String {
+            self.generate_id().await.to_string()
+        }
+
+        /// Asynchronously generate a new Snowflake ID encoded as a Base62 string.
+        #[must_use]
+        pub async fn generate_id_base62_string(&self) -> String {
+            crate::encoding::encode_base62(self.generate_id().await)
+        }
+
+        /// Asynchronously try to generate a new Snowflake ID without blocking.
+        ///
+        /// The state is shared by the clones rather than owned by a task, so this never
+        /// returns `SnowflakeError::GeneratorClosed`; see [`ChannelGenerator::try_generate_id`] for that.
+        ///
+        /// # Errors
+        /// Returns `SnowflakeError::SequenceExhausted` if the sequence for the current millisecond is used up.
+        /// Returns `SnowflakeError::ClockMovedBackwards` if the clock went backwards since the last ID.
+        /// Returns `SnowflakeError::TimestampOverflow` once the time since the epoch no longer fits the timestamp bits.
+        pub async fn try_generate_id(&self) -> Result<i64> {
+            let mut guard = self.inner.lock().await;
+            guard.try_generate_id()
+        }
+
+        /// Asynchronously generate a new Snowflake ID, returning an error instead of waiting
+        /// with `BackoffStrategy::Error`.
+        ///
+        /// # Errors
+        /// See `SnowflakeState::generate_id_with_backoff`.
+        pub async fn generate_id_with_backoff(&self) -> Result<i64> {
+            let mut guard = self.inner.lock().await;
+            guard.generate_id_with_backoff()
+        }
+
+        /// Set what the generator does when it has to wait for the clock.
+        pub async fn set_backoff_strategy(&self, backoff: crate::common::BackoffStrategy) {
+            let mut guard = self.inner.lock().await;
+            guard.set_backoff_strategy(backoff);
+        }
+
+        /// The ID the next call to `generate_id` would return, without consuming it.
+        #[must_use]
+        pub async fn peek_id(&self) -> i64 {
+            let guard = self.inner.lock().await;
+            guard.peek_id()
+        }
+
+        /// How many more IDs fit the current millisecond before generating has to wait.
+        pub async fn remaining_in_window(&self) -> u16 {
+            let guard = self.inner.lock().await;
+            guard.remaining_in_window()
+        }
+
+        /// The custom epoch in milliseconds, e.g. for [`crate::decompose_snowflake`].
+        pub async fn epoch(&self) -> i64 {
+            let guard = self.inner.lock().await;
+            guard.epoch
+        }
+
         /// Decompose a Snowflake ID into its components.
+        #[must_use]
         pub async fn decompose(&self, id: i64) -> crate::common::SnowflakeDecomposed {
             let guard = self.inner.lock().await;
             guard.decompose(id)
         }
+
+        /// Like `decompose`, but rejects negative IDs and timestamps overflowing with the epoch.
+        ///
+        /// # Errors
+        /// Fails like [`crate::decompose_snowflake`].
+        pub async fn try_decompose(&self, id: i64) -> Result<crate::common::SnowflakeDecomposed> {
+            let guard = self.inner.lock().await;
+            guard.try_decompose(id)
+        }
+
+        /// Counters of the IDs generated so far, and the current drift from the clock.
+        pub async fn stats(&self) -> crate::common::SnowflakeStats {
+            let guard = self.inner.lock().await;
+            guard.stats()
+        }
+
+        /// How far the timestamp of the last ID is ahead of the clock, in milliseconds.
+        /// See `SnowflakeState::drift_ms`.
+        pub async fn drift_ms(&self) -> i64 {
+            let guard = self.inner.lock().await;
+            guard.drift_ms()
+        }
     }
 
-    impl Clone for SnowflakeGenerator {
+    impl<C: ClockSource> Clone for SnowflakeGenerator<C> {
         fn clone(&self) -> Self {
             Self {
                 inner: self.inner.clone(),
             }
         }
     }
-}
 
-pub mod sync_generator {
-    use crate::common::SnowflakeState as Snowflake;
-    use crate::common::Result;
+    impl Default for SnowflakeGenerator {
+        fn default() -> Self {
+            Self::quick()
+        }
+    }
 
-    pub struct SnowflakeGenerator {
-        inner: std::sync::Arc<std::sync::Mutex<Snowflake>>,
+    impl<C: ClockSource + Send> crate::common::AsyncSnowflakeSource for SnowflakeGenerator<C> {
+        async fn generate_id(&self) -> i64 {
+            SnowflakeGenerator::generate_id(self).await
+        }
     }
 
-    impl SnowflakeGenerator {
-        /// Create a new synchronous Snowflake ID generator.
+    impl<C: ClockSource + Send + 'static> SnowflakeGenerator<C> {
+        /// An infinite stream of new Snowflake IDs, e.g. to `take(n)` with `StreamExt`.
+        ///
+        /// Each poll generates one ID, awaiting the Mutex like `generate_id`. The stream
+        /// holds its own handle to the state, so it can outlive this generator.
+        pub fn stream(&self) -> impl futures_core::Stream<Item = i64> + Send + 'static {
+            IdStream {
+                inner: self.inner.clone(),
+                pending: None,
+            }
+        }
+    }
+
+    type PendingId = std::pin::Pin<Box<dyn std::future::Future<Output = i64> + Send>>;
+
+    struct IdStream<C: ClockSource> {
+        inner: std::sync::Arc<tokio::sync::Mutex<Snowflake<C>>>,
+        /// The ID being generated, kept across polls while waiting for the Mutex.
+        pending: Option<PendingId>,
+    }
+
+    impl<C: ClockSource + Send + 'static> futures_core::Stream for IdStream<C> {
+        type Item = i64;
+
+        fn poll_next(
+            mut self: std::pin::Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<Option<i64>> {
+            let this = &mut *self;
+            let pending = this.pending.get_or_insert_with(|| {
+                let inner = this.inner.clone();
+                Box::pin(async move { inner.lock_owned().await.generate_id() })
+            });
+            let id = std::task::ready!(pending.as_mut().poll(cx));
+            this.pending = None;
+            std::task::Poll::Ready(Some(id))
+        }
+    }
+
+    /// The maximum number of queued requests served in one go by the owner task.
+    const CHANNEL_BATCH: usize = 1024;
+
+    /// A request sent to the owner task of a [`ChannelGenerator`].
+    enum Request {
+        Generate(tokio::sync::oneshot::Sender<Result<i64>>),
+        TryGenerate(tokio::sync::oneshot::Sender<Result<i64>>),
+        /// Stop after serving the requests queued so far, then reply once the state is dropped.
+        Shutdown(tokio::sync::oneshot::Sender<()>),
+    }
+
+    /// An asynchronous Snowflake ID generator backed by a dedicated owner task.
+    ///
+    /// The state is owned by a single spawned task, and callers request IDs over an
+    /// `mpsc` channel and await the reply on a `oneshot`. Requests queued while the task
+    /// is busy are served together in one batch, without any lock.
+    ///
+    /// Every ID costs a channel round-trip and a task wake-up, so raw throughput is usually
+    /// lower than [`SnowflakeGenerator`] (see `bench_multi_thread_async_mutex_vs_channel`).
+    /// Prefer it when many tasks generate IDs concurrently and waiting callers should be
+    /// served in arrival order without queueing on a lock; otherwise prefer the `Mutex` version.
+    #[must_use]
+    pub struct ChannelGenerator {
+        sender: tokio::sync::mpsc::Sender<Request>,
+    }
+
+    impl ChannelGenerator {
+        /// Create a new channel-backed Snowflake ID generator.
         /// 
         /// # Arguments
         /// * `epoch` - The custom epoch timestamp in milliseconds.
         /// * `worker_id` - The worker ID (0-1023).
         /// # Errors
         /// Returns `SnowflakeError::WorkerIdOutOfRange` if the worker_id is out of range.
+        /// Returns `SnowflakeError::EpochInFuture` if the epoch is set in the future relative to the current system time.
+        /// Returns `SnowflakeError::SystemTimeError` if the system time is before the Unix epoch.
+        /// # Panics
+        /// Panics if called outside of a Tokio runtime.
+        pub fn new(epoch: i64, worker_id: u16) -> Result<Self> {
+            Ok(Self::spawn(Snowflake::new(epoch, worker_id)?))
+        }
+
+        /// Create a generator with epoch 0 and worker ID 0, for tests and examples.
+        /// 
+        /// # Panics
+        /// Panics if the system time is before the Unix epoch.
+        /// With the `dedup-check` feature, panics if another live generator uses worker ID 0.
+        /// Panics if called outside of a Tokio runtime.
+        pub fn quick() -> Self {
+            Self::new(0, 0).expect("epoch 0 and worker ID 0 are valid")
+        }
+
+        /// Create a new channel-backed Snowflake ID generator using the Twitter-style
+        /// split of the worker bits into a datacenter ID and a worker ID.
+        /// 
+        /// # Arguments
+        /// * `epoch` - The custom epoch timestamp in milliseconds.
+        /// * `datacenter_id` - The datacenter ID (0-31).
+        /// * `worker_id` - The worker ID within the datacenter (0-31).
+        /// # Errors
+        /// Returns `SnowflakeError::DatacenterIdOutOfRange` or `SnowflakeError::WorkerIdOutOfRange`
+        /// if either ID is out of range.
+        /// Returns `SnowflakeError::EpochInFuture` if the epoch is set in the future relative to the current system time.
+        /// Returns `SnowflakeError::SystemTimeError` if the system time is before the Unix epoch.
+        /// # Panics
+        /// Panics if called outside of a Tokio runtime.
+        pub fn with_datacenter(epoch: i64, datacenter_id: u8, worker_id: u8) -> Result<Self> {
+            Ok(Self::spawn(Snowflake::with_datacenter(epoch, datacenter_id, worker_id)?))
+        }
+
+        /// Create a new channel-backed Snowflake ID generator driven by a custom clock.
         /// 
+        /// # Arguments
+        /// * `epoch` - The custom epoch timestamp in milliseconds.
+        /// * `worker_id` - The worker ID (0-1023).
+        /// * `clock` - The clock used to read the current time.
+        /// # Errors
+        /// Returns `SnowflakeError::WorkerIdOutOfRange` if the worker_id is out of range.
+        /// Returns `SnowflakeError::EpochInFuture` if the epoch is set in the future relative to the clock's current time.
         /// # Panics
-        /// Panics if the epoch is set in the future relative to the current system time.
+        /// Panics if called outside of a Tokio runtime.
+        pub fn with_clock<C: ClockSource + Send + 'static>(epoch: i64, worker_id: u16, clock: C) -> Result<Self> {
+            Ok(Self::spawn(Snowflake::with_clock(epoch, worker_id, clock)?))
+        }
+
+        fn spawn<C: ClockSource + Send + 'static>(mut state: Snowflake<C>) -> Self {
+            let (sender, mut receiver) = tokio::sync::mpsc::channel(CHANNEL_BATCH);
+            tokio::spawn(async move {
+                let mut batch: Vec<Request> = Vec::with_capacity(CHANNEL_BATCH);
+                let mut shutdown = None;
+                'serve: while receiver.recv_many(&mut batch, CHANNEL_BATCH).await > 0 {
+                    for request in batch.drain(..) {
+                        let reply = match request {
+                            Request::Generate(reply) => reply,
+                            Request::TryGenerate(reply) => {
+                                let _ = reply.send(state.try_generate_id());
+                                continue;
+                            }
+                            Request::Shutdown(done) => {
+                                shutdown = Some(done);
+                                break 'serve;
+                            }
+                        };
+                        let result = loop {
+                            match state.try_generate_id() {
+                                Err(SnowflakeError::SequenceExhausted) => {
+                                    tokio::time::sleep(std::time::Duration::from_millis(1)).await
+                                }
+                                Err(SnowflakeError::ClockMovedBackwards { by_ms }) => {
+                                    tokio::time::sleep(std::time::Duration::from_millis(by_ms as u64)).await
+                                }
+                                // Waiting cannot fix anything else, e.g. an exhausted epoch.
+                                result => break result,
+                            }
+                        };
+                        // The caller may have stopped waiting; its ID is simply dropped.
+                        let _ = reply.send(result);
+                    }
+                }
+                // Requests still queued are dropped, so their callers get `GeneratorClosed`.
+                drop(receiver);
+                drop(state);
+                if let Some(done) = shutdown {
+                    let _ = done.send(());
+                }
+            });
+            Self { sender }
+        }
+
+        /// Stop the owner task and wait for it to finish.
+        ///
+        /// Requests queued before the shutdown are still served. Afterwards, `generate_id`
+        /// on any clone of this generator returns `SnowflakeError::GeneratorClosed`.
+        /// Returns immediately if the task has already stopped.
+        pub async fn shutdown(self) {
+            let (done, finished) = tokio::sync::oneshot::channel();
+            if self.sender.send(Request::Shutdown(done)).await.is_ok() {
+                let _ = finished.await;
+            }
+        }
+
+        /// Asynchronously generate a new Snowflake ID.
+        /// 
+        /// # Errors
+        /// Returns `SnowflakeError::GeneratorClosed` if the generator was shut down or its
+        /// owner task has stopped, e.g. because its runtime was shut down.
+        /// Returns `SnowflakeError::TimestampOverflow` once the time since the epoch no longer
+        /// fits the timestamp bits, as waiting would never help.
+        pub async fn generate_id(&self) -> Result<i64> {
+            let (reply, response) = tokio::sync::oneshot::channel();
+            self.sender.send(Request::Generate(reply)).await.map_err(|_| SnowflakeError::GeneratorClosed)?;
+            response.await.map_err(|_| SnowflakeError::GeneratorClosed)?
+        }
+
+        /// Asynchronously try to generate a new Snowflake ID, without the owner task waiting
+        /// for the clock.
+        ///
+        /// # Errors
+        /// Returns `SnowflakeError::SequenceExhausted` if the sequence for the current millisecond is used up.
+        /// Returns `SnowflakeError::ClockMovedBackwards` if the clock went backwards since the last ID.
+        /// Returns `SnowflakeError::TimestampOverflow` once the time since the epoch no longer fits the timestamp bits.
+        /// Returns `SnowflakeError::GeneratorClosed` like `generate_id`.
+        pub async fn try_generate_id(&self) -> Result<i64> {
+            let (reply, response) = tokio::sync::oneshot::channel();
+            self.sender.send(Request::TryGenerate(reply)).await.map_err(|_| SnowflakeError::GeneratorClosed)?;
+            response.await.map_err(|_| SnowflakeError::GeneratorClosed)?
+        }
+
+        /// Asynchronously generate a new strongly-typed Snowflake ID.
+        /// 
+        /// # Errors
+        /// Fails like `generate_id`.
+        pub async fn generate(&self) -> Result<SnowflakeId> {
+            Ok(SnowflakeId::new(self.generate_id().await?))
+        }
+
+        /// Asynchronously generate a new Snowflake ID formatted as a decimal string.
+        /// 
+        /// # Errors
+        /// Fails like `generate_id`.
+        pub async fn generate_id_string(&self) -> Result<String> {
+            Ok(self.generate_id().await?.to_string())
+        }
+
+        /// Asynchronously generate a new Snowflake ID encoded as a Base62 string.
+        /// 
+        /// # Errors
+        /// Fails like `generate_id`.
+        pub async fn generate_id_base62_string(&self) -> Result<String> {
+            Ok(crate::encoding::encode_base62(self.generate_id().await?))
+        }
+    }
+
+    impl Clone for ChannelGenerator {
+        fn clone(&self) -> Self {
+            Self {
+                sender: self.sender.clone(),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async-std")]
+pub mod async_std_generator {
+    use crate::common::DefaultSnowflake as Snowflake;
+    use crate::common::{ClockSource, Result, SnowflakeError, SystemClock};
+    use crate::id::SnowflakeId;
+
+    /// An asynchronous Snowflake ID generator using async-std's Mutex for thread safety.
+    #[must_use]
+    pub struct SnowflakeGenerator<C: ClockSource = SystemClock> {
+        inner: std::sync::Arc<async_std::sync::Mutex<Snowflake<C>>>,
+    }
+
+    impl SnowflakeGenerator {
+
+        /// Create a new asynchronous Snowflake ID generator.
+        /// 
+        /// # Arguments
+        /// * `epoch` - The custom epoch timestamp in milliseconds.
+        /// * `worker_id` - The worker ID (0-1023).
+        /// # Errors
+        /// Returns `SnowflakeError::WorkerIdOutOfRange` if the worker_id is out of range.
+        /// Returns `SnowflakeError::EpochInFuture` if the epoch is set in the future relative to the current system time.
+        /// Returns `SnowflakeError::SystemTimeError` if the system time is before the Unix epoch.
         pub fn new(epoch: i64, worker_id: u16) -> Result<Self> {
             Ok(Self {
-                inner: std::sync::Arc::new(std::sync::Mutex::new(Snowflake::new(epoch, worker_id)?)),
+                inner: std::sync::Arc::new(async_std::sync::Mutex::new(Snowflake::new(epoch, worker_id)?)),
             })
         }
 
-        /// Generate a new Snowflake ID.
+        /// Create a generator with epoch 0 and worker ID 0, for tests and examples.
         /// 
         /// # Panics
-        /// Panics if the internal Mutex is poisoned.
-        pub fn generate_id(&self) -> i64 {
-            let mut guard = self.inner.lock();
-            match guard {
-                Ok(ref mut g) => g.generate_id(),
-                Err(e) => {
-                    panic!("Mutex poisoned: {}", e);
-                },
+        /// Panics if the system time is before the Unix epoch.
+        /// With the `dedup-check` feature, panics if another live generator uses worker ID 0.
+        pub fn quick() -> Self {
+            Self::new(0, 0).expect("epoch 0 and worker ID 0 are valid")
+        }
+
+        /// Create a new asynchronous Snowflake ID generator using the Twitter-style
+        /// split of the worker bits into a datacenter ID and a worker ID.
+        /// 
+        /// # Arguments
+        /// * `epoch` - The custom epoch timestamp in milliseconds.
+        /// * `datacenter_id` - The datacenter ID (0-31).
+        /// * `worker_id` - The worker ID within the datacenter (0-31).
+        /// # Errors
+        /// Returns `SnowflakeError::DatacenterIdOutOfRange` or `SnowflakeError::WorkerIdOutOfRange`
+        /// if either ID is out of range.
+        /// Returns `SnowflakeError::EpochInFuture` if the epoch is set in the future relative to the current system time.
+        /// Returns `SnowflakeError::SystemTimeError` if the system time is before the Unix epoch.
+        pub fn with_datacenter(epoch: i64, datacenter_id: u8, worker_id: u8) -> Result<Self> {
+            Ok(Self {
+                inner: std::sync::Arc::new(async_std::sync::Mutex::new(Snowflake::with_datacenter(epoch, datacenter_id, worker_id)?)),
+            })
+        }
+    }
+
+    impl<C: ClockSource> SnowflakeGenerator<C> {
+        /// Create a new asynchronous Snowflake ID generator driven by a custom clock.
+        /// 
+        /// # Arguments
+        /// * `epoch` - The custom epoch timestamp in milliseconds.
+        /// * `worker_id` - The worker ID (0-1023).
+        /// * `clock` - The clock used to read the current time.
+        /// # Errors
+        /// Returns `SnowflakeError::WorkerIdOutOfRange` if the worker_id is out of range.
+        /// Returns `SnowflakeError::EpochInFuture` if the epoch is set in the future relative to the clock's current time.
+        pub fn with_clock(epoch: i64, worker_id: u16, clock: C) -> Result<Self> {
+            Ok(Self {
+                inner: std::sync::Arc::new(async_std::sync::Mutex::new(Snowflake::with_clock(epoch, worker_id, clock)?)),
+            })
+        }
+
+        /// Asynchronously generate a new Snowflake ID.
+        #[must_use]
+        pub async fn generate_id(&self) -> i64 {
+            let mut guard = self.inner.lock().await;
+            guard.generate_id()
+        }
+
+        /// Asynchronously generate `n` monotonic Snowflake IDs, locking once per millisecond
+        /// instead of once per ID.
+        ///
+        /// When the 4096 IDs of a millisecond are used up (or the clock moves backwards),
+        /// the lock is released before sleeping until the next millisecond, so other callers
+        /// are not blocked. IDs from concurrent callers may therefore interleave with the batch,
+        /// but the returned IDs are always strictly increasing.
+        #[must_use]
+        pub async fn generate_ids(&self, n: usize) -> Vec<i64> {
+            let mut ids = Vec::with_capacity(n);
+            while ids.len() < n {
+                let wait_ms = {
+                    let mut guard = self.inner.lock().await;
+                    loop {
+                        if ids.len() == n {
+                            break 0;
+                        }
+                        match guard.try_generate_id() {
+                            Ok(id) => ids.push(id),
+                            Err(SnowflakeError::ClockMovedBackwards { by_ms }) => break by_ms as u64,
+                            Err(e @ SnowflakeError::TimestampOverflow) => panic!("Cannot generate an ID: {}", e),
+                            Err(_) => break 1,
+                        }
+                    }
+                };
+                if wait_ms > 0 {
+                    async_std::task::sleep(std::time::Duration::from_millis(wait_ms)).await;
+                }
             }
+            ids
+        }
+
+        /// Asynchronously generate a new strongly-typed Snowflake ID.
+        #[must_use]
+        pub async fn generate(&self) -> SnowflakeId {
+            SnowflakeId::new(self.generate_id().await)
+        }
+
+        /// Asynchronously generate a new Snowflake ID together with its components, taking
+        /// the lock only once.
+        #[must_use]
+        pub async fn generate_with_decomposed(&self) -> (i64, crate::common::SnowflakeDecomposed) {
+            let mut guard = self.inner.lock().await;
+            let id = guard.generate_id();
+            (id, guard.decompose(id))
+        }
+
+        /// Asynchronously generate a new Snowflake ID formatted as a decimal string.
+        #[must_use]
+        pub async fn generate_id_string(&self) -> String {
+            self.generate_id().await.to_string()
+        }
+
+        /// Asynchronously generate a new Snowflake ID encoded as a Base62 string.
+        #[must_use]
+        pub async fn generate_id_base62_string(&self) -> String {
+            crate::encoding::encode_base62(self.generate_id().await)
+        }
+
+        /// Asynchronously try to generate a new Snowflake ID without blocking.
+        ///
+        /// # Errors
+        /// Returns `SnowflakeError::SequenceExhausted` if the sequence for the current millisecond is used up.
+        /// Returns `SnowflakeError::ClockMovedBackwards` if the clock went backwards since the last ID.
+        /// Returns `SnowflakeError::TimestampOverflow` once the time since the epoch no longer fits the timestamp bits.
+        pub async fn try_generate_id(&self) -> Result<i64> {
+            let mut guard = self.inner.lock().await;
+            guard.try_generate_id()
+        }
+
+        /// The ID the next call to `generate_id` would return, without consuming it.
+        #[must_use]
+        pub async fn peek_id(&self) -> i64 {
+            let guard = self.inner.lock().await;
+            guard.peek_id()
+        }
+
+        /// How many more IDs fit the current millisecond before generating has to wait.
+        pub async fn remaining_in_window(&self) -> u16 {
+            let guard = self.inner.lock().await;
+            guard.remaining_in_window()
+        }
+
+        /// The custom epoch in milliseconds, e.g. for [`crate::decompose_snowflake`].
+        pub async fn epoch(&self) -> i64 {
+            let guard = self.inner.lock().await;
+            guard.epoch
         }
 
         /// Decompose a Snowflake ID into its components.
-        pub fn decompose(&self, id: i64) -> crate::common::SnowflakeDecomposed {
-            let guard = self.inner.lock();
-            match guard {
-                Ok(ref g) => g.decompose(id),
-                Err(e) => {
-                    panic!("Mutex poisoned: {}", e);
-                },
-            }
+        #[must_use]
+        pub async fn decompose(&self, id: i64) -> crate::common::SnowflakeDecomposed {
+            let guard = self.inner.lock().await;
+            guard.decompose(id)
+        }
+
+        /// Like `decompose`, but rejects negative IDs and timestamps overflowing with the epoch.
+        ///
+        /// # Errors
+        /// Fails like [`crate::decompose_snowflake`].
+        pub async fn try_decompose(&self, id: i64) -> Result<crate::common::SnowflakeDecomposed> {
+            let guard = self.inner.lock().await;
+            guard.try_decompose(id)
+        }
+
+        /// Counters of the IDs generated so far, and the current drift from the clock.
+        pub async fn stats(&self) -> crate::common::SnowflakeStats {
+            let guard = self.inner.lock().await;
+            guard.stats()
+        }
+
+        /// How far the timestamp of the last ID is ahead of the clock, in milliseconds.
+        /// See `SnowflakeState::drift_ms`.
+        pub async fn drift_ms(&self) -> i64 {
+            let guard = self.inner.lock().await;
+            guard.drift_ms()
         }
     }
 
-    impl Clone for SnowflakeGenerator {
+    impl<C: ClockSource> Clone for SnowflakeGenerator<C> {
         fn clone(&self) -> Self {
             Self {
                 inner: self.inner.clone(),
             }
         }
     }
-}
\ No newline at end of file
+
+    impl Default for SnowflakeGenerator {
+        fn default() -> Self {
+            Self::quick()
+        }
+    }
+
+    impl<C: ClockSource + Send> crate::common::AsyncSnowflakeSource for SnowflakeGenerator<C> {
+        async fn generate_id(&self) -> i64 {
+            SnowflakeGenerator::generate_id(self).await
+        }
+    }
+}
+
+pub mod sync_generator {
+    use crate::common::DefaultSnowflake as Snowflake;
+    use crate::common::{ClockSource, IdBlock, Result, SystemClock, SEQUENCE_BITS, TIMESTAMP_BITS, WORKER_BITS};
+    use crate::id::SnowflakeId;
+    use crate::raw::{FieldOrder, RawSnowflake};
+
+    #[must_use]
+    pub struct SnowflakeGenerator<C: ClockSource = SystemClock> {
+        inner: std::sync::Arc<std::sync::Mutex<Snowflake<C>>>,
+        // Immutable after construction, so decomposing never needs the lock.
+        epoch: i64,
+        field_order: FieldOrder,
+    }
+
+    impl SnowflakeGenerator {
+        /// Create a new synchronous Snowflake ID generator.
+        /// 
+        /// # Arguments
+        /// * `epoch` - The custom epoch timestamp in milliseconds.
+        /// * `worker_id` - The worker ID (0-1023).
+        /// # Errors
+        /// Returns `SnowflakeError::WorkerIdOutOfRange` if the worker_id is out of range.
+        /// Returns `SnowflakeError::EpochInFuture` if the epoch is set in the future relative to the current system time.
+        /// Returns `SnowflakeError::SystemTimeError` if the system time is before the Unix epoch.
+        pub fn new(epoch: i64, worker_id: u16) -> Result<Self> {
+            Ok(Self {
+                inner: std::sync::Arc::new(std::sync::Mutex::new(Snowflake::new(epoch, worker_id)?)),
+                epoch,
+                field_order: FieldOrder::default(),
+            })
+        }
+
+        /// Create a generator with epoch 0 and worker ID 0, for tests and examples.
+        /// 
+        /// # Panics
+        /// Panics if the system time is before the Unix epoch.
+        /// With the `dedup-check` feature, panics if another live generator uses worker ID 0.
+        pub fn quick() -> Self {
+            Self::new(0, 0).expect("epoch 0 and worker ID 0 are valid")
+        }
+
+        /// Create a new synchronous Snowflake ID generator with a worker ID picked at random
+        /// from `pool`, e.g. for ephemeral workers.
+        ///
+        /// With the `dedup-check` feature, if the picked worker ID is used by a live generator
+        /// of the process, the following ones of the pool are tried in turn, wrapping around.
+        ///
+        /// # Arguments
+        /// * `epoch` - The custom epoch timestamp in milliseconds.
+        /// * `rng` - The random number generator picking the worker ID.
+        /// * `pool` - The worker IDs to pick from (within 0-1023).
+        /// # Errors
+        /// Returns `SnowflakeError::WorkerIdOutOfRange` if the pool is empty or out of range.
+        /// Returns `SnowflakeError::DuplicateWorkerId` if every worker ID of the pool is in use.
+        /// Otherwise fails like `new`.
+        #[cfg(feature = "rand")]
+        pub fn new_random_worker<R: rand::Rng + ?Sized>(
+            epoch: i64,
+            rng: &mut R,
+            pool: std::ops::Range<u16>,
+        ) -> Result<Self> {
+            if pool.is_empty() || pool.end > crate::common::MAX_WORKER_ID + 1 {
+                return Err(crate::common::SnowflakeError::WorkerIdOutOfRange);
+            }
+            let len = pool.end - pool.start;
+            let first = rng.random_range(0..len);
+            for i in 0..len {
+                match Self::new(epoch, pool.start + (first + i) % len) {
+                    Err(crate::common::SnowflakeError::DuplicateWorkerId) => continue,
+                    result => return result,
+                }
+            }
+            Err(crate::common::SnowflakeError::DuplicateWorkerId)
+        }
+
+        /// Create a new synchronous Snowflake ID generator using the Twitter-style
+        /// split of the worker bits into a datacenter ID and a worker ID.
+        /// 
+        /// # Arguments
+        /// * `epoch` - The custom epoch timestamp in milliseconds.
+        /// * `datacenter_id` - The datacenter ID (0-31).
+        /// * `worker_id` - The worker ID within the datacenter (0-31).
+        /// # Errors
+        /// Returns `SnowflakeError::DatacenterIdOutOfRange` or `SnowflakeError::WorkerIdOutOfRange`
+        /// if either ID is out of range.
+        /// Returns `SnowflakeError::EpochInFuture` if the epoch is set in the future relative to the current system time.
+        /// Returns `SnowflakeError::SystemTimeError` if the system time is before the Unix epoch.
+        pub fn with_datacenter(epoch: i64, datacenter_id: u8, worker_id: u8) -> Result<Self> {
+            Ok(Self {
+                inner: std::sync::Arc::new(std::sync::Mutex::new(Snowflake::with_datacenter(epoch, datacenter_id, worker_id)?)),
+                epoch,
+                field_order: FieldOrder::default(),
+            })
+        }
+    }
+
+    impl<C: ClockSource> SnowflakeGenerator<C> {
+        /// Create a new synchronous Snowflake ID generator driven by a custom clock.
+        /// 
+        /// # Arguments
+        /// * `epoch` - The custom epoch timestamp in milliseconds.
+        /// * `worker_id` - The worker ID (0-1023).
+        /// * `clock` - The clock used to read the current time.
+        /// # Errors
+        /// Returns `SnowflakeError::WorkerIdOutOfRange` if the worker_id is out of range.
+        /// Returns `SnowflakeError::EpochInFuture` if the epoch is set in the future relative to the clock's current time.
+        pub fn with_clock(epoch: i64, worker_id: u16, clock: C) -> Result<Self> {
+            Ok(Self {
+                inner: std::sync::Arc::new(std::sync::Mutex::new(Snowflake::with_clock(epoch, worker_id, clock)?)),
+                epoch,
+                field_order: FieldOrder::default(),
+            })
+        }
+
+        pub(crate) fn from_state(state: Snowflake<C>) -> Self {
+            Self {
+                epoch: state.epoch,
+                field_order: state.field_order,
+                inner: std::sync::Arc::new(std::sync::Mutex::new(state)),
+            }
+        }
+
+        /// Generate a new Snowflake ID.
+        /// 
+        /// # Panics
+        /// Panics if the internal Mutex is poisoned.
+        #[must_use]
+        pub fn generate_id(&self) -> i64 {
+            let mut guard = self.inner.lock();
+            match guard {
+                Ok(ref mut g) => g.generate_id(),
+                Err(e) => {
+                    panic!("Mutex poisoned: {}", e);
+                },
+            }
+        }
+
+        /// Generate a new Snowflake ID using all 64 bits, for consumers storing IDs unsigned.
+        ///
+        /// See [`RawSnowflake::generate_id_u64`](crate::raw::RawSnowflake::generate_id_u64)
+        /// for how it relates to the i64 IDs, and decompose it with [`crate::decompose_u64`].
+        /// 
+        /// # Panics
+        /// Panics if the internal Mutex is poisoned.
+        #[must_use]
+        pub fn generate_id_u64(&self) -> u64 {
+            let mut guard = self.inner.lock();
+            match guard {
+                Ok(ref mut g) => g.generate_id_u64(),
+                Err(e) => {
+                    panic!("Mutex poisoned: {}", e);
+                },
+            }
+        }
+
+        /// Generate `n` monotonic Snowflake IDs while holding the lock only once.
+        ///
+        /// Sequence rollover is handled by sleeping until the next millisecond with the lock held.
+        /// 
+        /// # Panics
+        /// Panics if the internal Mutex is poisoned.
+        #[must_use]
+        pub fn generate_ids(&self, n: usize) -> Vec<i64> {
+            let mut guard = self.inner.lock();
+            match guard {
+                Ok(ref mut g) => (0..n).map(|_| g.generate_id()).collect(),
+                Err(e) => {
+                    panic!("Mutex poisoned: {}", e);
+                },
+            }
+        }
+
+        /// Fill `buf` with monotonic Snowflake IDs while holding the lock only once, without
+        /// allocating.
+        ///
+        /// Sequence rollover is handled by sleeping until the next millisecond with the lock held.
+        /// 
+        /// # Panics
+        /// Panics if the internal Mutex is poisoned.
+        pub fn generate_into(&self, buf: &mut [i64]) {
+            let mut guard = self.inner.lock();
+            match guard {
+                Ok(ref mut g) => buf.iter_mut().for_each(|slot| *slot = g.generate_id()),
+                Err(e) => {
+                    panic!("Mutex poisoned: {}", e);
+                },
+            }
+        }
+
+        /// Generate `n` monotonic Snowflake IDs using all cores.
+        ///
+        /// Ranges of consecutive sequence numbers are reserved for each millisecond under a
+        /// single lock acquisition, sleeping with the lock held on sequence rollover like
+        /// [`Self::generate_ids`]. The IDs are then filled in parallel with rayon.
+        /// 
+        /// # Panics
+        /// Panics if the internal Mutex is poisoned.
+        #[cfg(feature = "rayon")]
+        #[must_use]
+        pub fn generate_ids_parallel(&self, n: usize) -> Vec<i64> {
+            use rayon::prelude::*;
+            let (ranges, step) = match self.inner.lock() {
+                Ok(mut g) => (g.reserve_ids(n), g.id_step()),
+                Err(e) => {
+                    panic!("Mutex poisoned: {}", e);
+                },
+            };
+            ranges
+                .into_par_iter()
+                .flat_map_iter(|(first, count)| (0..count as i64).map(move |i| first + i * step))
+                .collect()
+        }
+
+        /// Generate a new Snowflake ID, returning an error instead of waiting with
+        /// `BackoffStrategy::Error`.
+        ///
+        /// # Errors
+        /// See `SnowflakeState::generate_id_with_backoff`.
+        ///
+        /// # Panics
+        /// Panics if the internal Mutex is poisoned.
+        pub fn generate_id_with_backoff(&self) -> Result<i64> {
+            let mut guard = self.inner.lock();
+            match guard {
+                Ok(ref mut g) => g.generate_id_with_backoff(),
+                Err(e) => {
+                    panic!("Mutex poisoned: {}", e);
+                },
+            }
+        }
+
+        /// Generate a new Snowflake ID stamped with `worker_id` instead of the generator's own,
+        /// e.g. to take over the ID space of a failed node during failover.
+        ///
+        /// # Errors
+        /// See `SnowflakeState::generate_id_as`.
+        ///
+        /// # Panics
+        /// Panics if the internal Mutex is poisoned.
+        pub fn generate_id_as(&self, worker_id: u16) -> Result<i64> {
+            let mut guard = self.inner.lock();
+            match guard {
+                Ok(ref mut g) => g.generate_id_as(worker_id),
+                Err(e) => {
+                    panic!("Mutex poisoned: {}", e);
+                },
+            }
+        }
+
+        /// Generate a new Snowflake ID strictly greater than `floor`, waiting for the clock
+        /// while it is behind `floor`.
+        ///
+        /// # Errors
+        /// See `SnowflakeState::generate_id_after`.
+        ///
+        /// # Panics
+        /// Panics if the internal Mutex is poisoned.
+        pub fn generate_id_after(&self, floor: i64) -> Result<i64> {
+            let mut guard = self.inner.lock();
+            match guard {
+                Ok(ref mut g) => g.generate_id_after(floor),
+                Err(e) => {
+                    panic!("Mutex poisoned: {}", e);
+                },
+            }
+        }
+
+        /// Reserve `n` consecutive IDs of one millisecond under a single lock acquisition,
+        /// e.g. to pre-allocate primary keys.
+        ///
+        /// If fewer than `n` IDs are left in the current millisecond, waits for the next one
+        /// as set by the backoff strategy, holding the lock.
+        ///
+        /// # Errors
+        /// See `SnowflakeState::reserve`.
+        ///
+        /// # Panics
+        /// Panics if the internal Mutex is poisoned.
+        pub fn reserve(&self, n: u16) -> Result<IdBlock> {
+            let mut guard = self.inner.lock();
+            match guard {
+                Ok(ref mut g) => g.reserve(n),
+                Err(e) => {
+                    panic!("Mutex poisoned: {}", e);
+                },
+            }
+        }
+
+        /// Set what the generator does when it has to wait for the clock.
+        /// 
+        /// # Panics
+        /// Panics if the internal Mutex is poisoned.
+        pub fn set_backoff_strategy(&self, backoff: crate::common::BackoffStrategy) {
+            let mut guard = self.inner.lock();
+            match guard {
+                Ok(ref mut g) => g.set_backoff_strategy(backoff),
+                Err(e) => {
+                    panic!("Mutex poisoned: {}", e);
+                },
+            }
+        }
+
+        /// Set how leaps of the clock are detected and smoothed.
+        /// See [`ForwardJumpPolicy`](crate::ForwardJumpPolicy).
+        /// 
+        /// # Panics
+        /// Panics if the internal Mutex is poisoned.
+        pub fn set_forward_jump_policy(&self, policy: crate::common::ForwardJumpPolicy) {
+            let mut guard = self.inner.lock();
+            match guard {
+                Ok(ref mut g) => g.set_forward_jump_policy(policy),
+                Err(e) => {
+                    panic!("Mutex poisoned: {}", e);
+                },
+            }
+        }
+
+        /// Generate a new strongly-typed Snowflake ID.
+        /// 
+        /// # Panics
+        /// Panics if the internal Mutex is poisoned.
+        #[must_use]
+        pub fn generate(&self) -> SnowflakeId {
+            SnowflakeId::new(self.generate_id())
+        }
+
+        /// Generate a new Snowflake ID together with its components, taking the lock only once.
+        /// 
+        /// # Panics
+        /// Panics if the internal Mutex is poisoned.
+        #[must_use]
+        pub fn generate_with_decomposed(&self) -> (i64, crate::common::SnowflakeDecomposed) {
+            let mut guard = self.inner.lock();
+            match guard {
+                Ok(ref mut g) => {
+                    let id = g.generate_id();
+                    (id, g.decompose(id))
+                }
+                Err(e) => {
+                    panic!("Mutex poisoned: {}", e);
+                },
+            }
+        }
+
+        /// Generate a new Snowflake ID formatted as a decimal string.
+        /// 
+        /// # Panics
+        /// Panics if the internal Mutex is poisoned.
+        #[must_use]
+        pub fn generate_id_string(&self) -> String {
+            self.generate_id().to_string()
+        }
+
+        /// Generate a new Snowflake ID encoded as a Base62 string.
+        /// 
+        /// # Panics
+        /// Panics if the internal Mutex is poisoned.
+        #[must_use]
+        pub fn generate_id_base62_string(&self) -> String {
+            crate::encoding::encode_base62(self.generate_id())
+        }
+
+        /// Try to generate a new Snowflake ID without blocking.
+        ///
+        /// # Errors
+        /// Returns `SnowflakeError::SequenceExhausted` if the sequence for the current millisecond is used up.
+        /// Returns `SnowflakeError::ClockMovedBackwards` if the clock went backwards since the last ID.
+        /// Returns `SnowflakeError::TimestampOverflow` once the time since the epoch no longer fits the timestamp bits.
+        ///
+        /// # Panics
+        /// Panics if the internal Mutex is poisoned.
+        pub fn try_generate_id(&self) -> Result<i64> {
+            let mut guard = self.inner.lock();
+            match guard {
+                Ok(ref mut g) => g.try_generate_id(),
+                Err(e) => {
+                    panic!("Mutex poisoned: {}", e);
+                },
+            }
+        }
+
+        /// Generate an ID for an explicit past timestamp, for backfilling historical records.
+        ///
+        /// # Errors
+        /// Returns `SnowflakeError::TimestampBeforeEpoch` if the timestamp is before the epoch.
+        /// Returns `SnowflakeError::TimestampNotInPast` if the timestamp is not in the past.
+        /// Returns `SnowflakeError::SequenceExhausted` if the sequence for that millisecond is used up.
+        ///
+        /// # Panics
+        /// Panics if the internal Mutex is poisoned.
+        pub fn generate_id_at(&self, timestamp_ms: i64) -> Result<i64> {
+            let mut guard = self.inner.lock();
+            match guard {
+                Ok(ref mut g) => g.generate_id_at(timestamp_ms),
+                Err(e) => {
+                    panic!("Mutex poisoned: {}", e);
+                },
+            }
+        }
+
+        /// The ID the next call to `generate_id` would return, without consuming it.
+        /// 
+        /// # Panics
+        /// Panics if the internal Mutex is poisoned.
+        #[must_use]
+        pub fn peek_id(&self) -> i64 {
+            let guard = self.inner.lock();
+            match guard {
+                Ok(ref g) => g.peek_id(),
+                Err(e) => {
+                    panic!("Mutex poisoned: {}", e);
+                },
+            }
+        }
+
+        /// How many more IDs fit the current millisecond before generating has to wait.
+        /// 
+        /// # Panics
+        /// Panics if the internal Mutex is poisoned.
+        pub fn remaining_in_window(&self) -> u16 {
+            let guard = self.inner.lock();
+            match guard {
+                Ok(ref g) => g.remaining_in_window(),
+                Err(e) => {
+                    panic!("Mutex poisoned: {}", e);
+                },
+            }
+        }
+
+        /// The custom epoch in milliseconds, e.g. for [`crate::decompose_snowflake`].
+        pub fn epoch(&self) -> i64 {
+            self.epoch
+        }
+
+        /// Decompose a Snowflake ID into its components.
+        ///
+        /// Does not take the lock, so it never waits for ID generation.
+        #[must_use]
+        pub fn decompose(&self, id: i64) -> crate::common::SnowflakeDecomposed {
+            RawSnowflake::<TIMESTAMP_BITS, WORKER_BITS, SEQUENCE_BITS>::decompose_ordered(id, self.epoch, self.field_order)
+        }
+
+        /// Like `decompose`, but rejects negative IDs and timestamps overflowing with the epoch.
+        ///
+        /// # Errors
+        /// Fails like [`crate::decompose_snowflake`].
+        pub fn try_decompose(&self, id: i64) -> Result<crate::common::SnowflakeDecomposed> {
+            RawSnowflake::<TIMESTAMP_BITS, WORKER_BITS, SEQUENCE_BITS>::try_decompose_ordered(id, self.epoch, self.field_order)
+        }
+
+        /// Counters of the IDs generated so far, and the current drift from the clock.
+        /// 
+        /// # Panics
+        /// Panics if the internal Mutex is poisoned.
+        pub fn stats(&self) -> crate::common::SnowflakeStats {
+            let guard = self.inner.lock();
+            match guard {
+                Ok(ref g) => g.stats(),
+                Err(e) => {
+                    panic!("Mutex poisoned: {}", e);
+                },
+            }
+        }
+
+        /// How far the timestamp of the last ID is ahead of the clock, in milliseconds.
+        /// See `SnowflakeState::drift_ms`.
+        ///
+        /// # Panics
+        /// Panics if the internal Mutex is poisoned.
+        pub fn drift_ms(&self) -> i64 {
+            let guard = self.inner.lock();
+            match guard {
+                Ok(ref g) => g.drift_ms(),
+                Err(e) => {
+                    panic!("Mutex poisoned: {}", e);
+                },
+            }
+        }
+
+        /// Save the state, to be restored with
+        /// [`SnowflakeState::from_bytes`](crate::SnowflakeState::from_bytes).
+        /// See [`SnowflakeState::to_bytes`](crate::SnowflakeState::to_bytes).
+        /// 
+        /// # Panics
+        /// Panics if the internal Mutex is poisoned.
+        pub fn to_bytes(&self) -> [u8; crate::common::SNAPSHOT_LEN] {
+            let guard = self.inner.lock();
+            match guard {
+                Ok(ref g) => g.to_bytes(),
+                Err(e) => {
+                    panic!("Mutex poisoned: {}", e);
+                },
+            }
+        }
+    }
+
+    impl<C: ClockSource> Clone for SnowflakeGenerator<C> {
+        fn clone(&self) -> Self {
+            Self {
+                inner: self.inner.clone(),
+                epoch: self.epoch,
+                field_order: self.field_order,
+            }
+        }
+    }
+
+    impl Default for SnowflakeGenerator {
+        fn default() -> Self {
+            Self::quick()
+        }
+    }
+
+    impl<C: ClockSource> crate::common::SnowflakeSource for SnowflakeGenerator<C> {
+        fn generate_id(&self) -> i64 {
+            SnowflakeGenerator::generate_id(self)
+        }
+    }
+
+    /// A [`SnowflakeGenerator`] saving its state to a sink when dropped, so that a clean
+    /// shutdown always persists the latest sequence.
+    ///
+    /// Dereferences to the wrapped generator. The state is saved once, when the
+    /// `PersistentGenerator` is dropped: IDs generated afterwards through clones of the
+    /// wrapped generator are not covered.
+    ///
+    /// `Drop` does not run if the process aborts or exits without unwinding, e.g. on a panic
+    /// with `panic = "abort"` or `std::process::exit`. Nothing is saved either if a panic
+    /// poisoned the internal Mutex.
+    #[must_use]
+    pub struct PersistentGenerator<C: ClockSource = SystemClock> {
+        generator: SnowflakeGenerator<C>,
+        sink: Sink,
+    }
+
+    type Sink = Box<dyn Fn(&[u8]) + Send + Sync>;
+
+    impl<C: ClockSource> PersistentGenerator<C> {
+        /// Wrap `generator`, calling `sink` with the bytes of
+        /// [`SnowflakeState::to_bytes`](crate::SnowflakeState::to_bytes) when dropped.
+        pub fn new(generator: SnowflakeGenerator<C>, sink: impl Fn(&[u8]) + Send + Sync + 'static) -> Self {
+            Self {
+                generator,
+                sink: Box::new(sink),
+            }
+        }
+    }
+
+    impl<C: ClockSource> std::ops::Deref for PersistentGenerator<C> {
+        type Target = SnowflakeGenerator<C>;
+
+        fn deref(&self) -> &Self::Target {
+            &self.generator
+        }
+    }
+
+    impl<C: ClockSource> Drop for PersistentGenerator<C> {
+        fn drop(&mut self) {
+            if let Ok(state) = self.generator.inner.lock() {
+                (self.sink)(&state.to_bytes());
+            }
+        }
+    }
+}
+
+#[cfg(feature = "parking_lot")]
+pub mod parking_lot_generator {
+    use crate::common::DefaultSnowflake as Snowflake;
+    use crate::common::{ClockSource, Result, SystemClock};
+    use crate::id::SnowflakeId;
+
+    /// A synchronous Snowflake ID generator backed by `parking_lot::Mutex`.
+    ///
+    /// A drop-in replacement for `sync_generator::SnowflakeGenerator` that never panics
+    /// because of lock poisoning, as `parking_lot` locks do not poison.
+    #[must_use]
+    pub struct SnowflakeGenerator<C: ClockSource = SystemClock> {
+        inner: std::sync::Arc<parking_lot::Mutex<Snowflake<C>>>,
+    }
+
+    impl SnowflakeGenerator {
+        /// Create a new synchronous Snowflake ID generator.
+        /// 
+        /// # Arguments
+        /// * `epoch` - The custom epoch timestamp in milliseconds.
+        /// * `worker_id` - The worker ID (0-1023).
+        /// # Errors
+        /// Returns `SnowflakeError::WorkerIdOutOfRange` if the worker_id is out of range.
+        /// Returns `SnowflakeError::EpochInFuture` if the epoch is set in the future relative to the current system time.
+        /// Returns `SnowflakeError::SystemTimeError` if the system time is before the Unix epoch.
+        pub fn new(epoch: i64, worker_id: u16) -> Result<Self> {
+            Ok(Self {
+                inner: std::sync::Arc::new(parking_lot::Mutex::new(Snowflake::new(epoch, worker_id)?)),
+            })
+        }
+
+        /// Create a generator with epoch 0 and worker ID 0, for tests and examples.
+        /// 
+        /// # Panics
+        /// Panics if the system time is before the Unix epoch.
+        /// With the `dedup-check` feature, panics if another live generator uses worker ID 0.
+        pub fn quick() -> Self {
+            Self::new(0, 0).expect("epoch 0 and worker ID 0 are valid")
+        }
+
+        /// Create a new synchronous Snowflake ID generator using the Twitter-style
+        /// split of the worker bits into a datacenter ID and a worker ID.
+        /// 
+        /// # Arguments
+        /// * `epoch` - The custom epoch timestamp in milliseconds.
+        /// * `datacenter_id` - The datacenter ID (0-31).
+        /// * `worker_id` - The worker ID within the datacenter (0-31).
+        /// # Errors
+        /// Returns `SnowflakeError::DatacenterIdOutOfRange` or `SnowflakeError::WorkerIdOutOfRange`
+        /// if either ID is out of range.
+        /// Returns `SnowflakeError::EpochInFuture` if the epoch is set in the future relative to the current system time.
+        /// Returns `SnowflakeError::SystemTimeError` if the system time is before the Unix epoch.
+        pub fn with_datacenter(epoch: i64, datacenter_id: u8, worker_id: u8) -> Result<Self> {
+            Ok(Self {
+                inner: std::sync::Arc::new(parking_lot::Mutex::new(Snowflake::with_datacenter(epoch, datacenter_id, worker_id)?)),
+            })
+        }
+    }
+
+    impl<C: ClockSource> SnowflakeGenerator<C> {
+        /// Create a new synchronous Snowflake ID generator driven by a custom clock.
+        /// 
+        /// # Arguments
+        /// * `epoch` - The custom epoch timestamp in milliseconds.
+        /// * `worker_id` - The worker ID (0-1023).
+        /// * `clock` - The clock used to read the current time.
+        /// # Errors
+        /// Returns `SnowflakeError::WorkerIdOutOfRange` if the worker_id is out of range.
+        /// Returns `SnowflakeError::EpochInFuture` if the epoch is set in the future relative to the clock's current time.
+        pub fn with_clock(epoch: i64, worker_id: u16, clock: C) -> Result<Self> {
+            Ok(Self {
+                inner: std::sync::Arc::new(parking_lot::Mutex::new(Snowflake::with_clock(epoch, worker_id, clock)?)),
+            })
+        }
+
+        /// Generate a new Snowflake ID.
+        #[must_use]
+        pub fn generate_id(&self) -> i64 {
+            let mut guard = self.inner.lock();
+            guard.generate_id()
+        }
+
+        /// Generate `n` monotonic Snowflake IDs while holding the lock only once.
+        ///
+        /// Sequence rollover is handled by sleeping until the next millisecond with the lock held.
+        #[must_use]
+        pub fn generate_ids(&self, n: usize) -> Vec<i64> {
+            let mut guard = self.inner.lock();
+            (0..n).map(|_| guard.generate_id()).collect()
+        }
+
+        /// Fill `buf` with monotonic Snowflake IDs while holding the lock only once, without
+        /// allocating.
+        ///
+        /// Sequence rollover is handled by sleeping until the next millisecond with the lock held.
+        pub fn generate_into(&self, buf: &mut [i64]) {
+            let mut guard = self.inner.lock();
+            buf.iter_mut().for_each(|slot| *slot = guard.generate_id());
+        }
+
+        /// Generate a new strongly-typed Snowflake ID.
+        #[must_use]
+        pub fn generate(&self) -> SnowflakeId {
+            SnowflakeId::new(self.generate_id())
+        }
+
+        /// Generate a new Snowflake ID together with its components, taking the lock only once.
+        #[must_use]
+        pub fn generate_with_decomposed(&self) -> (i64, crate::common::SnowflakeDecomposed) {
+            let mut guard = self.inner.lock();
+            let id = guard.generate_id();
+            (id, guard.decompose(id))
+        }
+
+        /// Generate a new Snowflake ID formatted as a decimal string.
+        #[must_use]
+        pub fn generate_id_string(&self) -> String {
+            self.generate_id().to_string()
+        }
+
+        /// Generate a new Snowflake ID encoded as a Base62 string.
+        #[must_use]
+        pub fn generate_id_base62_string(&self) -> String {
+            crate::encoding::encode_base62(self.generate_id())
+        }
+
+        /// Try to generate a new Snowflake ID without blocking.
+        ///
+        /// # Errors
+        /// Returns `SnowflakeError::SequenceExhausted` if the sequence for the current millisecond is used up.
+        /// Returns `SnowflakeError::ClockMovedBackwards` if the clock went backwards since the last ID.
+        /// Returns `SnowflakeError::TimestampOverflow` once the time since the epoch no longer fits the timestamp bits.
+        pub fn try_generate_id(&self) -> Result<i64> {
+            let mut guard = self.inner.lock();
+            guard.try_generate_id()
+        }
+
+        /// Generate an ID for an explicit past timestamp, for backfilling historical records.
+        ///
+        /// # Errors
+        /// Returns `SnowflakeError::TimestampBeforeEpoch` if the timestamp is before the epoch.
+        /// Returns `SnowflakeError::TimestampNotInPast` if the timestamp is not in the past.
+        /// Returns `SnowflakeError::SequenceExhausted` if the sequence for that millisecond is used up.
+        pub fn generate_id_at(&self, timestamp_ms: i64) -> Result<i64> {
+            let mut guard = self.inner.lock();
+            guard.generate_id_at(timestamp_ms)
+        }
+
+        /// The ID the next call to `generate_id` would return, without consuming it.
+        #[must_use]
+        pub fn peek_id(&self) -> i64 {
+            let guard = self.inner.lock();
+            guard.peek_id()
+        }
+
+        /// How many more IDs fit the current millisecond before generating has to wait.
+        pub fn remaining_in_window(&self) -> u16 {
+            let guard = self.inner.lock();
+            guard.remaining_in_window()
+        }
+
+        /// The custom epoch in milliseconds, e.g. for [`crate::decompose_snowflake`].
+        pub fn epoch(&self) -> i64 {
+            self.inner.lock().epoch
+        }
+
+        /// Decompose a Snowflake ID into its components.
+        #[must_use]
+        pub fn decompose(&self, id: i64) -> crate::common::SnowflakeDecomposed {
+            let guard = self.inner.lock();
+            guard.decompose(id)
+        }
+
+        /// Like `decompose`, but rejects negative IDs and timestamps overflowing with the epoch.
+        ///
+        /// # Errors
+        /// Fails like [`crate::decompose_snowflake`].
+        pub fn try_decompose(&self, id: i64) -> Result<crate::common::SnowflakeDecomposed> {
+            let guard = self.inner.lock();
+            guard.try_decompose(id)
+        }
+
+        /// Counters of the IDs generated so far, and the current drift from the clock.
+        pub fn stats(&self) -> crate::common::SnowflakeStats {
+            let guard = self.inner.lock();
+            guard.stats()
+        }
+
+        /// How far the timestamp of the last ID is ahead of the clock, in milliseconds.
+        /// See `SnowflakeState::drift_ms`.
+        pub fn drift_ms(&self) -> i64 {
+            let guard = self.inner.lock();
+            guard.drift_ms()
+        }
+    }
+
+    impl<C: ClockSource> Clone for SnowflakeGenerator<C> {
+        fn clone(&self) -> Self {
+            Self {
+                inner: self.inner.clone(),
+            }
+        }
+    }
+
+    impl Default for SnowflakeGenerator {
+        fn default() -> Self {
+            Self::quick()
+        }
+    }
+
+    impl<C: ClockSource> crate::common::SnowflakeSource for SnowflakeGenerator<C> {
+        fn generate_id(&self) -> i64 {
+            SnowflakeGenerator::generate_id(self)
+        }
+    }
+}
+
+pub mod atomic_generator {
+    use crate::common::{
+        decompose_const, ClockSource, Result, SnowflakeError, SystemClock, MAX_SEQUENCE, MAX_WORKER_ID, SEQUENCE_BITS,
+        TIMESTAMP_BITS, WORKER_BITS,
+    };
+    use crate::id::SnowflakeId;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    const SEQUENCE_MASK: u64 = MAX_SEQUENCE as u64;
+    const TIMESTAMP_MAX: u64 = (1 << TIMESTAMP_BITS) - 1;
+
+    struct AtomicState<C> {
+        /// The last issued `time_since_epoch << SEQUENCE_BITS | sequence`.
+        last: AtomicU64,
+        worker_id: u16,
+        epoch: i64,
+        clock: C,
+        #[cfg(feature = "dedup-check")]
+        _worker_id_guard: crate::registry::WorkerIdGuard,
+    }
+
+    /// A lock-free Snowflake ID generator claiming sequence numbers with a CAS loop.
+    ///
+    /// Uses the default 41/10/12 layout and produces the same IDs as the Mutex-based generators.
+    #[must_use]
+    pub struct SnowflakeGenerator<C: ClockSource = SystemClock> {
+        inner: std::sync::Arc<AtomicState<C>>,
+    }
+
+    impl SnowflakeGenerator {
+        /// Create a new lock-free Snowflake ID generator.
+        /// 
+        /// # Arguments
+        /// * `epoch` - The custom epoch timestamp in milliseconds.
+        /// * `worker_id` - The worker ID (0-1023).
+        /// # Errors
+        /// Returns `SnowflakeError::WorkerIdOutOfRange` if the worker_id is out of range.
+        /// Returns `SnowflakeError::EpochInFuture` if the epoch is set in the future relative to the current system time.
+        /// Returns `SnowflakeError::SystemTimeError` if the system time is before the Unix epoch.
+        pub fn new(epoch: i64, worker_id: u16) -> Result<Self> {
+            Self::with_clock(epoch, worker_id, SystemClock::new()?)
+        }
+
+        /// Create a generator with epoch 0 and worker ID 0, for tests and examples.
+        /// 
+        /// # Panics
+        /// Panics if the system time is before the Unix epoch.
+        /// With the `dedup-check` feature, panics if another live generator uses worker ID 0.
+        pub fn quick() -> Self {
+            Self::new(0, 0).expect("epoch 0 and worker ID 0 are valid")
+        }
+    }
+
+    impl<C: ClockSource> SnowflakeGenerator<C> {
+        /// Create a new lock-free Snowflake ID generator driven by a custom clock.
+        /// 
+        /// # Arguments
+        /// * `epoch` - The custom epoch timestamp in milliseconds.
+        /// * `worker_id` - The worker ID (0-1023).
+        /// * `clock` - The clock used to read the current time.
+        /// # Errors
+        /// Returns `SnowflakeError::WorkerIdOutOfRange` if the worker_id is out of range.
+        /// Returns `SnowflakeError::EpochInFuture` if the epoch is set in the future relative to the clock's current time.
+        /// Returns `SnowflakeError::DuplicateWorkerId` with the `dedup-check` feature if another
+        /// live generator already uses the worker_id in this process.
+        pub fn with_clock(epoch: i64, worker_id: u16, clock: C) -> Result<Self> {
+            if worker_id > MAX_WORKER_ID {
+                return Err(SnowflakeError::WorkerIdOutOfRange);
+            }
+            if clock.now_millis() < epoch {
+                return Err(SnowflakeError::EpochInFuture);
+            }
+            Ok(Self {
+                inner: std::sync::Arc::new(AtomicState {
+                    last: AtomicU64::new(0),
+                    worker_id,
+                    epoch,
+                    clock,
+                    #[cfg(feature = "dedup-check")]
+                    _worker_id_guard: crate::registry::WorkerIdGuard::acquire(worker_id)?,
+                }),
+            })
+        }
+
+        /// Generate a new Snowflake ID without taking a lock.
+        ///
+        /// When the sequence for the current millisecond is exhausted, or the clock moved
+        /// backwards past the sequence of the last issued millisecond, the thread yields
+        /// until the clock catches up so IDs stay monotonic.
+        ///
+        /// # Panics
+        /// Panics once the time since the epoch no longer fits the 41 timestamp bits, like
+        /// the Mutex-based generators.
+        #[must_use]
+        pub fn generate_id(&self) -> i64 {
+            let state = &self.inner;
+            loop {
+                let last = state.last.load(Ordering::Acquire);
+                let last_time = last >> SEQUENCE_BITS;
+                let current_time = self.current_time();
+                let next = if current_time > last_time {
+                    if current_time > TIMESTAMP_MAX {
+                        panic!("Cannot generate an ID: {}", SnowflakeError::TimestampOverflow);
+                    }
+                    current_time << SEQUENCE_BITS
+                } else if last & SEQUENCE_MASK < SEQUENCE_MASK {
+                    last + 1
+                } else {
+                    std::thread::yield_now();
+                    continue;
+                };
+                if state
+                    .last
+                    .compare_exchange_weak(last, next, Ordering::AcqRel, Ordering::Acquire)
+                    .is_ok()
+                {
+                    return self.to_i64(next);
+                }
+            }
+        }
+
+        /// The ID the next call to `generate_id` would return, without consuming it.
+        ///
+        /// Another thread may claim that ID before this thread generates one.
+        #[must_use]
+        pub fn peek_id(&self) -> i64 {
+            let last = self.inner.last.load(Ordering::Acquire);
+            let current_time = self.current_time();
+            let next = if current_time > last >> SEQUENCE_BITS {
+                current_time << SEQUENCE_BITS
+            } else if last & SEQUENCE_MASK < SEQUENCE_MASK {
+                last + 1
+            } else {
+                ((last >> SEQUENCE_BITS) + 1) << SEQUENCE_BITS
+            };
+            self.to_i64(next)
+        }
+
+        fn current_time(&self) -> u64 {
+            self.inner.clock.now_millis().saturating_sub(self.inner.epoch).max(0) as u64
+        }
+
+        fn to_i64(&self, packed: u64) -> i64 {
+            let time_since_epoch = (packed >> SEQUENCE_BITS) as i64;
+            let sequence = (packed & SEQUENCE_MASK) as i64;
+            ((time_since_epoch << (WORKER_BITS + SEQUENCE_BITS)) | ((self.inner.worker_id as i64) << SEQUENCE_BITS) | sequence)
+                & 0x7FFFFFFFFFFFFFFF
+        }
+
+        /// Generate a new strongly-typed Snowflake ID.
+        #[must_use]
+        pub fn generate(&self) -> SnowflakeId {
+            SnowflakeId::new(self.generate_id())
+        }
+
+        /// Generate a new Snowflake ID formatted as a decimal string.
+        #[must_use]
+        pub fn generate_id_string(&self) -> String {
+            self.generate_id().to_string()
+        }
+
+        /// Generate a new Snowflake ID encoded as a Base62 string.
+        #[must_use]
+        pub fn generate_id_base62_string(&self) -> String {
+            crate::encoding::encode_base62(self.generate_id())
+        }
+
+        /// How many more IDs fit the current millisecond before generating has to wait.
+        ///
+        /// Other threads may use them up before this thread generates one. Like
+        /// [`SnowflakeState::remaining_in_window`](crate::common::SnowflakeState::remaining_in_window),
+        /// this is 0 while the clock is behind the last ID, although `generate_id` keeps
+        /// issuing the rest of that millisecond's sequence meanwhile.
+        pub fn remaining_in_window(&self) -> u16 {
+            let last = self.inner.last.load(Ordering::Acquire);
+            match self.current_time().cmp(&(last >> SEQUENCE_BITS)) {
+                std::cmp::Ordering::Greater => MAX_SEQUENCE + 1,
+                std::cmp::Ordering::Equal => (SEQUENCE_MASK - (last & SEQUENCE_MASK)) as u16,
+                std::cmp::Ordering::Less => 0,
+            }
+        }
+
+        /// The custom epoch in milliseconds, e.g. for [`crate::decompose_snowflake`].
+        pub fn epoch(&self) -> i64 {
+            self.inner.epoch
+        }
+
+        /// Decompose a Snowflake ID into its components.
+        #[must_use]
+        pub fn decompose(&self, id: i64) -> crate::common::SnowflakeDecomposed {
+            decompose_const(id, self.inner.epoch)
+        }
+
+        /// Like `decompose`, but rejects negative IDs and timestamps overflowing with the epoch.
+        ///
+        /// # Errors
+        /// Fails like [`crate::decompose_snowflake`].
+        pub fn try_decompose(&self, id: i64) -> Result<crate::common::SnowflakeDecomposed> {
+            crate::common::decompose_snowflake(id, self.inner.epoch)
+        }
+    }
+
+    impl<C: ClockSource> Clone for SnowflakeGenerator<C> {
+        fn clone(&self) -> Self {
+            Self {
+                inner: self.inner.clone(),
+            }
+        }
+    }
+
+    impl Default for SnowflakeGenerator {
+        fn default() -> Self {
+            Self::quick()
+        }
+    }
+
+    impl<C: ClockSource> crate::common::SnowflakeSource for SnowflakeGenerator<C> {
+        fn generate_id(&self) -> i64 {
+            SnowflakeGenerator::generate_id(self)
+        }
+    }
+}
@@ -0,0 +1,621 @@
+use crate::common::{
+    splitmix64, with_checksum, IdBlock, Result, SnowflakeDecomposed, SnowflakeError, CHECKSUM_BITS, SEQUENCE_BITS, TIMESTAMP_BITS,
+};
+
+/// The order of the worker ID and sequence fields below the timestamp.
+///
+/// IDs generated with one order cannot be decomposed with the other, and IDs of both
+/// orders may collide, so a deployment must stick to one. Only the classic order is
+/// understood by the free functions such as [`decompose_snowflake`](crate::decompose_snowflake).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum FieldOrder {
+    /// The classic Snowflake order, with the sequence in the lowest bits.
+    #[default]
+    WorkerThenSequence,
+    /// The sequence above the worker ID, which is in the lowest bits, e.g. for index
+    /// layouts scanning the IDs of a single worker over a time range.
+    SequenceThenWorker,
+}
+
+/// Clock-free Snowflake state usable without `std`.
+///
+/// Every operation that depends on the time takes the current time in milliseconds
+/// since the Unix epoch as an explicit argument, and nothing ever sleeps.
+///
+/// `TS_BITS`, `WORKER_BITS` and `SEQ_BITS` must sum to 63, with at most 16 worker
+/// bits and 15 sequence bits. This is checked at compile time, so using an invalid
+/// layout fails to build:
+///
+/// ```compile_fail
+/// let raw = snowflake_id_generator::raw::RawSnowflake::<40, 10, 12>::new(0, 1, 0);
+/// ```
+///
+/// ```compile_fail
+/// let raw = snowflake_id_generator::raw::RawSnowflake::<30, 17, 16>::new(0, 1, 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RawSnowflake<
+    const TS_BITS: usize = TIMESTAMP_BITS,
+    const WORKER_BITS: usize = { crate::common::WORKER_BITS },
+    const SEQ_BITS: usize = SEQUENCE_BITS,
+> {
+    pub time_since_epoch: i64,
+    pub worker_id: u16,
+    pub sequence: u16,
+    pub epoch: i64,
+    /// How much the sequence advances per ID, see [`Self::with_sequence_stride`].
+    pub sequence_step: u16,
+    /// The first sequence number of every millisecond.
+    pub sequence_offset: u16,
+    /// The order of the worker ID and sequence fields, see [`Self::with_field_order`].
+    pub field_order: FieldOrder,
+}
+
+impl<const TS_BITS: usize, const WORKER_BITS: usize, const SEQ_BITS: usize> RawSnowflake<TS_BITS, WORKER_BITS, SEQ_BITS> {
+    pub(crate) const TIMESTAMP_SHIFT: usize = WORKER_BITS + SEQ_BITS;
+    pub(crate) const WORKER_MASK: i64 = (1 << WORKER_BITS) - 1;
+    pub(crate) const SEQUENCE_MASK: i64 = (1 << SEQ_BITS) - 1;
+    /// The largest time since the epoch that fits the timestamp bits.
+    pub(crate) const TIMESTAMP_MAX: i64 = (1 << TS_BITS) - 1;
+
+    /// # Errors
+    /// Returns `SnowflakeError::WorkerIdOutOfRange` if the worker_id does not fit the layout,
+    /// `SnowflakeError::EpochInFuture` if `now_millis` is before the epoch and
+    /// `SnowflakeError::TimestampOverflow` if the time since the epoch does not fit an i64.
+    pub fn new(epoch: i64, worker_id: u16, now_millis: i64) -> Result<Self> {
+        const {
+            assert!(
+                TS_BITS + WORKER_BITS + SEQ_BITS == 63 && WORKER_BITS <= 16 && SEQ_BITS <= 15,
+                "bit widths must sum to 63, with at most 16 worker bits and 15 sequence bits"
+            )
+        };
+        if worker_id as i64 > Self::WORKER_MASK {
+            return Err(SnowflakeError::WorkerIdOutOfRange);
+        }
+        if now_millis < epoch {
+            return Err(SnowflakeError::EpochInFuture);
+        }
+        now_millis.checked_sub(epoch).ok_or(SnowflakeError::TimestampOverflow)?;
+        // SAFETY: the worker ID and the time since the epoch were checked above.
+        Ok(unsafe { Self::new_unchecked(epoch, worker_id, now_millis) })
+    }
+
+    /// Like `new`, but without checking the worker ID and epoch.
+    ///
+    /// # Safety
+    /// The worker_id must fit the layout (0-1023 for the default layout), and `now_millis`
+    /// must not be before the epoch nor more than `i64::MAX` milliseconds after it.
+    /// Otherwise the generated IDs may carry another worker ID or collide.
+    #[must_use]
+    pub const unsafe fn new_unchecked(epoch: i64, worker_id: u16, now_millis: i64) -> Self {
+        const {
+            assert!(
+                TS_BITS + WORKER_BITS + SEQ_BITS == 63 && WORKER_BITS <= 16 && SEQ_BITS <= 15,
+                "bit widths must sum to 63, with at most 16 worker bits and 15 sequence bits"
+            )
+        };
+        Self {
+            time_since_epoch: now_millis.wrapping_sub(epoch),
+            worker_id,
+            epoch,
+            sequence: 0,
+            sequence_step: 1,
+            sequence_offset: 0,
+            field_order: FieldOrder::WorkerThenSequence,
+        }
+    }
+
+    /// Place the worker ID and sequence fields in the given order. See [`FieldOrder`].
+    pub fn with_field_order(mut self, order: FieldOrder) -> Self {
+        self.field_order = order;
+        self
+    }
+
+    /// The shifts of the worker ID and sequence fields for the given order.
+    const fn field_shifts(order: FieldOrder) -> (usize, usize) {
+        match order {
+            FieldOrder::WorkerThenSequence => (SEQ_BITS, 0),
+            FieldOrder::SequenceThenWorker => (0, WORKER_BITS),
+        }
+    }
+
+    /// The difference between consecutive IDs of one millisecond.
+    pub(crate) fn id_step(&self) -> i64 {
+        (self.sequence_step as i64) << Self::field_shifts(self.field_order).1
+    }
+
+    /// Hand out only the sequence numbers `offset, offset + step, offset + 2 * step, ...`
+    /// of every millisecond, so that generators with the same worker ID and different
+    /// offsets produce disjoint IDs.
+    ///
+    /// # Errors
+    /// Returns `SnowflakeError::InvalidSequenceStride` if `step` is 0 or does not divide
+    /// the sequence space (4096 for the default layout), or if `offset` is not below `step`.
+    pub fn with_sequence_stride(mut self, step: u16, offset: u16) -> Result<Self> {
+        if step == 0 || (Self::SEQUENCE_MASK + 1) % step as i64 != 0 || offset >= step {
+            return Err(SnowflakeError::InvalidSequenceStride);
+        }
+        self.sequence_step = step;
+        self.sequence_offset = offset;
+        self.sequence = offset;
+        Ok(self)
+    }
+
+    /// Start the current millisecond at a sequence number derived from `seed`, instead of
+    /// the first one, to spread the low bits of the first IDs across nodes restarted together.
+    ///
+    /// The start is deterministic for a given seed, and respects the sequence stride. It
+    /// never moves the sequence backwards, so IDs already generated in this millisecond
+    /// cannot be repeated. Later milliseconds start at the first sequence number as usual.
+    pub fn randomize_initial_sequence(&mut self, seed: u64) {
+        // Consecutive seeds give unrelated sequence numbers.
+        let z = splitmix64(seed);
+        let slots = (Self::SEQUENCE_MASK as u64 + 1) / self.sequence_step as u64;
+        let start = self.sequence_offset + (z % slots) as u16 * self.sequence_step;
+        self.sequence = self.sequence.max(start);
+    }
+
+    fn to_i64(&self) -> i64 {
+        self.pack(self.time_since_epoch, self.sequence)
+    }
+
+    pub(crate) fn pack(&self, time_since_epoch: i64, sequence: u16) -> i64 {
+        self.pack_as(time_since_epoch, self.worker_id, sequence)
+    }
+
+    fn pack_as(&self, time_since_epoch: i64, worker_id: u16, sequence: u16) -> i64 {
+        debug_assert!(sequence as i64 <= Self::SEQUENCE_MASK, "sequence overflows into the worker bits");
+        let (worker_shift, sequence_shift) = Self::field_shifts(self.field_order);
+        ((time_since_epoch << Self::TIMESTAMP_SHIFT) | ((worker_id as i64) << worker_shift) | ((sequence as i64) << sequence_shift))
+            & 0x7FFFFFFFFFFFFFFF
+    }
+
+    fn pack_u64(&self, time_since_epoch: i64, sequence: u16) -> u64 {
+        debug_assert!(sequence as i64 <= Self::SEQUENCE_MASK, "sequence overflows into the worker bits");
+        let (worker_shift, sequence_shift) = Self::field_shifts(self.field_order);
+        ((time_since_epoch as u64) << Self::TIMESTAMP_SHIFT) | ((self.worker_id as u64) << worker_shift) | ((sequence as u64) << sequence_shift)
+    }
+
+    /// Generate a new Snowflake ID for the given time.
+    ///
+    /// Returns `SnowflakeError::SequenceExhausted` if all sequence numbers for the
+    /// current millisecond have been used. The state is left untouched in that case,
+    /// so the call can simply be retried later.
+    ///
+    /// Returns `SnowflakeError::ClockMovedBackwards` if `now_millis` is earlier than
+    /// the last generated ID, instead of emitting a non-monotonic ID.
+    ///
+    /// Returns `SnowflakeError::TimestampOverflow` once the time since the epoch no longer
+    /// fits the timestamp bits, instead of wrapping into the sign bit.
+    pub fn generate_id(&mut self, now_millis: i64) -> Result<i64> {
+        self.advance(now_millis, Self::TIMESTAMP_MAX)?;
+        let id = self.to_i64();
+
+        self.sequence += self.sequence_step;
+        Ok(id)
+    }
+
+    /// Like [`Self::generate_id`], but stamps the ID with `worker_id` instead of this state's
+    /// worker ID, e.g. to take over the ID space of a failed node.
+    ///
+    /// The sequence is shared with `generate_id`, so IDs generated as any worker in the same
+    /// millisecond never collide, at the cost of sharing the sequence of that millisecond.
+    ///
+    /// # Errors
+    /// Returns `SnowflakeError::WorkerIdOutOfRange` if the worker_id does not fit the layout.
+    /// Otherwise fails like `generate_id`.
+    pub fn generate_id_as(&mut self, now_millis: i64, worker_id: u16) -> Result<i64> {
+        if worker_id as i64 > Self::WORKER_MASK {
+            return Err(SnowflakeError::WorkerIdOutOfRange);
+        }
+        self.advance(now_millis, Self::TIMESTAMP_MAX)?;
+        let id = self.pack_as(self.time_since_epoch, worker_id, self.sequence);
+
+        self.sequence += self.sequence_step;
+        Ok(id)
+    }
+
+    /// Like [`Self::generate_id`], but returns an ID strictly greater than `floor`, skipping
+    /// the sequence numbers of the millisecond of `floor` that would not be.
+    ///
+    /// # Errors
+    /// Returns `SnowflakeError::TimestampInFuture` if `now_millis` is before the first
+    /// millisecond holding an ID greater than `floor`, e.g. when `floor` was generated by a
+    /// higher worker ID in the current millisecond. The state is left untouched in that case.
+    /// Returns `SnowflakeError::TimestampOverflow` if that millisecond does not fit the
+    /// timestamp bits. Otherwise fails like `generate_id`.
+    pub fn generate_id_after(&mut self, now_millis: i64, floor: i64) -> Result<i64> {
+        let (min_time, min_sequence) = self.first_slot_after(floor);
+        if min_time > Self::TIMESTAMP_MAX {
+            return Err(SnowflakeError::TimestampOverflow);
+        }
+        if now_millis.saturating_sub(self.epoch) < min_time {
+            return Err(SnowflakeError::TimestampInFuture);
+        }
+        self.advance(now_millis, Self::TIMESTAMP_MAX)?;
+        if self.time_since_epoch == min_time {
+            self.sequence = self.sequence.max(min_sequence);
+        }
+        let id = self.to_i64();
+
+        self.sequence += self.sequence_step;
+        Ok(id)
+    }
+
+    /// The millisecond and sequence number of the first ID of this state greater than `floor`.
+    fn first_slot_after(&self, floor: i64) -> (i64, u16) {
+        if floor < 0 {
+            return (0, self.sequence_offset);
+        }
+        let (worker_shift, sequence_shift) = Self::field_shifts(self.field_order);
+        let time = floor >> Self::TIMESTAMP_SHIFT;
+        let worker_id = (floor >> worker_shift) & Self::WORKER_MASK;
+        let sequence = (floor >> sequence_shift) & Self::SEQUENCE_MASK;
+        // The lowest sequence number of `time` giving an ID greater than `floor`.
+        let min_sequence = match (self.field_order, (self.worker_id as i64).cmp(&worker_id)) {
+            (FieldOrder::WorkerThenSequence, core::cmp::Ordering::Greater) => 0,
+            (FieldOrder::WorkerThenSequence, core::cmp::Ordering::Equal) => sequence + 1,
+            (FieldOrder::WorkerThenSequence, core::cmp::Ordering::Less) => return (time + 1, self.sequence_offset),
+            (FieldOrder::SequenceThenWorker, core::cmp::Ordering::Greater) => sequence,
+            (FieldOrder::SequenceThenWorker, _) => sequence + 1,
+        };
+        // Round up to a sequence number of the stride.
+        let (step, offset) = (self.sequence_step as i64, self.sequence_offset as i64);
+        let min_sequence = offset + ((min_sequence - offset).max(0) + step - 1) / step * step;
+        if min_sequence > Self::SEQUENCE_MASK {
+            (time + 1, self.sequence_offset)
+        } else {
+            (time, min_sequence as u16)
+        }
+    }
+
+    /// Like [`Self::generate_id`], but uses all 64 bits, with one more timestamp bit than
+    /// the layout (42 bits for the default layout, lasting about 139 years after the epoch).
+    ///
+    /// Up to `2^TS_BITS` milliseconds after the epoch, the value equals the ID `generate_id`
+    /// would return. Beyond that, the top bit is set, so the ID no longer fits a signed
+    /// 64-bit column without being reinterpreted as negative.
+    ///
+    /// Fails like `generate_id`, except that `SnowflakeError::TimestampOverflow` is only
+    /// returned once the extra timestamp bit is used up too.
+    pub fn generate_id_u64(&mut self, now_millis: i64) -> Result<u64> {
+        self.advance(now_millis, (Self::TIMESTAMP_MAX << 1) | 1)?;
+        let id = self.pack_u64(self.time_since_epoch, self.sequence);
+
+        self.sequence += self.sequence_step;
+        Ok(id)
+    }
+
+    /// Like [`Self::generate_id`], but with the low 2 bits of the sequence holding a
+    /// checksum of the rest of the ID, so that mistyped IDs can be detected with
+    /// [`verify_checksum`](crate::verify_checksum).
+    ///
+    /// Each ID uses up an aligned block of 4 sequence numbers, so a millisecond only holds
+    /// a quarter of the IDs (1024 with the default layout). IDs from `generate_id` on the
+    /// same state never collide with these, as the sequence only moves forward.
+    ///
+    /// # Errors
+    /// Fails like `generate_id`.
+    /// Returns `SnowflakeError::InvalidSequenceStride` if a sequence stride is set, as the
+    /// checksum needs every sequence number.
+    /// Returns `SnowflakeError::UnsupportedFieldOrder` unless the sequence is in the lowest bits.
+    pub fn generate_id_with_checksum(&mut self, now_millis: i64) -> Result<i64> {
+        const { assert!(SEQ_BITS >= CHECKSUM_BITS, "the checksum needs at least 2 sequence bits") };
+        if self.sequence_step != 1 {
+            return Err(SnowflakeError::InvalidSequenceStride);
+        }
+        if self.field_order != FieldOrder::WorkerThenSequence {
+            return Err(SnowflakeError::UnsupportedFieldOrder);
+        }
+        self.advance(now_millis, Self::TIMESTAMP_MAX)?;
+        // Only the current millisecond can be exhausted, which `advance` leaves untouched.
+        let block = self.sequence.next_multiple_of(1 << CHECKSUM_BITS);
+        if block as i64 > Self::SEQUENCE_MASK {
+            return Err(SnowflakeError::SequenceExhausted);
+        }
+        let id = with_checksum(self.pack(self.time_since_epoch, block));
+
+        self.sequence = block + (1 << CHECKSUM_BITS);
+        Ok(id)
+    }
+
+    /// Move to the millisecond of `now_millis`, leaving the state untouched on errors.
+    fn advance(&mut self, now_millis: i64, max_time_since_epoch: i64) -> Result<()> {
+        let current_time = now_millis.checked_sub(self.epoch).ok_or(SnowflakeError::TimestampOverflow)?;
+        if current_time > max_time_since_epoch {
+            return Err(SnowflakeError::TimestampOverflow);
+        }
+        if current_time < self.time_since_epoch {
+            return Err(SnowflakeError::ClockMovedBackwards {
+                by_ms: self.time_since_epoch - current_time,
+            });
+        }
+        if self.time_since_epoch == current_time {
+            // `sequence` holds the next value to hand out, so exceeding the mask means every
+            // sequence number of this millisecond has been used.
+            if self.sequence as i64 > Self::SEQUENCE_MASK {
+                return Err(SnowflakeError::SequenceExhausted);
+            }
+        } else {
+            self.time_since_epoch = current_time;
+            self.sequence = self.sequence_offset;
+        }
+        Ok(())
+    }
+
+    /// Reserve `n` consecutive IDs of one millisecond at once, e.g. to pre-allocate keys.
+    ///
+    /// # Errors
+    /// Returns `SnowflakeError::BlockTooLarge` if `n` exceeds the IDs of a whole millisecond
+    /// (4096 with the default layout, fewer with a stride).
+    /// Returns `SnowflakeError::SequenceExhausted` if fewer than `n` IDs are left in the
+    /// current millisecond, leaving them to later calls. Otherwise fails like `generate_id`,
+    /// except for empty blocks, which never fail.
+    pub fn reserve_block(&mut self, now_millis: i64, n: u16) -> Result<IdBlock> {
+        let per_millisecond = (Self::SEQUENCE_MASK - self.sequence_offset as i64) / self.sequence_step as i64 + 1;
+        if n as i64 > per_millisecond {
+            return Err(SnowflakeError::BlockTooLarge);
+        }
+        if n == 0 {
+            return Ok(IdBlock::new(0, 0, self.id_step()));
+        }
+        self.advance(now_millis, Self::TIMESTAMP_MAX)?;
+        // A new millisecond holds `per_millisecond` IDs, so only the current one can fall short.
+        if n > self.remaining_in_window(self.epoch + self.time_since_epoch) {
+            return Err(SnowflakeError::SequenceExhausted);
+        }
+        let first = self.to_i64();
+
+        self.sequence += n * self.sequence_step;
+        Ok(IdBlock::new(first, n, self.id_step()))
+    }
+
+    /// Reserve up to `max` consecutive IDs of the current millisecond at once.
+    ///
+    /// Returns the first reserved ID and the number of IDs reserved. The reserved IDs are
+    /// `first + i * self.id_step()` for `i` in `0..count`.
+    /// Fails like `generate_id`.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn reserve_ids(&mut self, now_millis: i64, max: usize) -> Result<(i64, usize)> {
+        let first = self.generate_id(now_millis)?;
+        let available = (Self::SEQUENCE_MASK + 1 - self.sequence as i64).max(0) as usize;
+        let step = self.sequence_step as usize;
+        let count = max.min(available.div_ceil(step) + 1);
+        self.sequence += ((count - 1) * step) as u16;
+        Ok((first, count))
+    }
+
+    /// Generate a new Snowflake ID for the given time without ever waiting.
+    ///
+    /// When the sequence of the current millisecond is exhausted, the ID is borrowed from
+    /// the following millisecond instead of waiting for it. If `now_millis` is behind the
+    /// last generated ID, because of borrowing or because the clock moved backwards, IDs
+    /// keep being generated from the last millisecond.
+    ///
+    /// Timestamps can therefore run ahead of `now_millis`, by one millisecond for every
+    /// `2^SEQ_BITS` IDs generated beyond the capacity of the elapsed milliseconds, and
+    /// fall back in step once the burst is over.
+    ///
+    /// # Errors
+    /// Returns `SnowflakeError::TimestampOverflow` if the timestamp, including a borrowed
+    /// millisecond, no longer fits the timestamp bits. The state is left untouched.
+    pub fn generate_id_monotonic(&mut self, now_millis: i64) -> Result<i64> {
+        let current_time = now_millis.saturating_sub(self.epoch);
+        let (time_since_epoch, sequence) = if current_time > self.time_since_epoch {
+            (current_time, self.sequence_offset)
+        } else if self.sequence as i64 > Self::SEQUENCE_MASK {
+            (self.time_since_epoch + 1, self.sequence_offset)
+        } else {
+            (self.time_since_epoch, self.sequence)
+        };
+        if time_since_epoch > Self::TIMESTAMP_MAX {
+            return Err(SnowflakeError::TimestampOverflow);
+        }
+        self.time_since_epoch = time_since_epoch;
+        let id = self.pack(time_since_epoch, sequence);
+
+        self.sequence = sequence + self.sequence_step;
+        Ok(id)
+    }
+
+    /// The ID the next call to `generate_id` at `now_millis` would return, without consuming it.
+    ///
+    /// If the sequence for the current millisecond is exhausted, this is the first ID of
+    /// the following millisecond.
+    #[must_use]
+    pub fn peek_id(&self, now_millis: i64) -> i64 {
+        let current_time = now_millis.saturating_sub(self.epoch);
+        if current_time > self.time_since_epoch {
+            self.pack(current_time, self.sequence_offset)
+        } else if self.sequence as i64 > Self::SEQUENCE_MASK {
+            self.pack(self.time_since_epoch + 1, self.sequence_offset)
+        } else {
+            self.to_i64()
+        }
+    }
+
+    /// How many more IDs `generate_id` at `now_millis` can return within that millisecond.
+    ///
+    /// A millisecond after the last ID starts with the full sequence (4096 IDs with the
+    /// default layout, fewer with a stride). If the clock moved backwards, no ID can be
+    /// generated until it caught up, so this is 0.
+    pub fn remaining_in_window(&self, now_millis: i64) -> u16 {
+        let current_time = now_millis.saturating_sub(self.epoch);
+        let sequence = match current_time.cmp(&self.time_since_epoch) {
+            core::cmp::Ordering::Greater => self.sequence_offset,
+            core::cmp::Ordering::Equal => self.sequence,
+            core::cmp::Ordering::Less => return 0,
+        };
+        if sequence as i64 > Self::SEQUENCE_MASK {
+            return 0;
+        }
+        ((Self::SEQUENCE_MASK - sequence as i64) / self.sequence_step as i64 + 1) as u16
+    }
+
+    /// Check whether an ID carries this state's worker ID. Negative IDs are never ours.
+    pub fn is_from_self(&self, id: i64) -> bool {
+        id >= 0 && self.decompose(id).worker_id == self.worker_id
+    }
+
+    /// Like [`Self::is_from_self`], but reports why the ID was rejected.
+    ///
+    /// # Errors
+    /// Returns `SnowflakeError::NegativeId` if the sign bit of the ID is set.
+    /// Returns `SnowflakeError::WorkerIdMismatch` if the ID was generated by another worker.
+    pub fn assert_from_self(&self, id: i64) -> Result<()> {
+        if id < 0 {
+            return Err(SnowflakeError::NegativeId);
+        }
+        let found = self.decompose(id).worker_id;
+        if found != self.worker_id {
+            return Err(SnowflakeError::WorkerIdMismatch {
+                expected: self.worker_id,
+                found,
+            });
+        }
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn decompose(&self, id: i64) -> SnowflakeDecomposed {
+        Self::decompose_ordered(id, self.epoch, self.field_order)
+    }
+
+    /// Like [`Self::decompose`], but rejects IDs that cannot have been generated.
+    ///
+    /// # Errors
+    /// Returns `SnowflakeError::NegativeId` if the sign bit is set.
+    /// Returns `SnowflakeError::TimestampOverflow` if adding the epoch to the timestamp overflows.
+    pub fn try_decompose(&self, id: i64) -> Result<SnowflakeDecomposed> {
+        Self::try_decompose_ordered(id, self.epoch, self.field_order)
+    }
+
+    pub(crate) fn try_decompose_with_epoch(id: i64, epoch: i64) -> Result<SnowflakeDecomposed> {
+        Self::check_decomposable(id, epoch)?;
+        Ok(Self::decompose_with_epoch(id, epoch))
+    }
+
+    pub(crate) fn try_decompose_ordered(id: i64, epoch: i64, order: FieldOrder) -> Result<SnowflakeDecomposed> {
+        Self::check_decomposable(id, epoch)?;
+        Ok(Self::decompose_ordered(id, epoch, order))
+    }
+
+    fn check_decomposable(id: i64, epoch: i64) -> Result<()> {
+        if id < 0 {
+            return Err(SnowflakeError::NegativeId);
+        }
+        if (id >> Self::TIMESTAMP_SHIFT).checked_add(epoch).is_none() {
+            return Err(SnowflakeError::TimestampOverflow);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn try_compose_with_epoch(decomposed: &SnowflakeDecomposed, epoch: i64) -> Result<i64> {
+        let ts = decomposed.timestamp.checked_sub(epoch).ok_or(SnowflakeError::TimestampOverflow)?;
+        if ts < 0 {
+            return Err(SnowflakeError::TimestampBeforeEpoch);
+        }
+        if ts > Self::TIMESTAMP_MAX {
+            return Err(SnowflakeError::TimestampOverflow);
+        }
+        if decomposed.worker_id as i64 > Self::WORKER_MASK {
+            return Err(SnowflakeError::WorkerIdOutOfRange);
+        }
+        if decomposed.sequence as i64 > Self::SEQUENCE_MASK {
+            return Err(SnowflakeError::SequenceOutOfRange);
+        }
+        let (worker_shift, sequence_shift) = Self::field_shifts(FieldOrder::WorkerThenSequence);
+        Ok((ts << Self::TIMESTAMP_SHIFT)
+            | ((decomposed.worker_id as i64) << worker_shift)
+            | ((decomposed.sequence as i64) << sequence_shift))
+    }
+
+    /// Decompose an ID generated with [`Self::generate_id_u64`].
+    #[must_use]
+    pub fn decompose_u64(&self, id: u64) -> SnowflakeDecomposed {
+        Self::decompose_u64_ordered(id, self.epoch, self.field_order)
+    }
+
+    pub(crate) const fn decompose_u64_with_epoch(id: u64, epoch: i64) -> SnowflakeDecomposed {
+        Self::decompose_u64_ordered(id, epoch, FieldOrder::WorkerThenSequence)
+    }
+
+    const fn decompose_u64_ordered(id: u64, epoch: i64, order: FieldOrder) -> SnowflakeDecomposed {
+        let (worker_shift, sequence_shift) = Self::field_shifts(order);
+        SnowflakeDecomposed {
+            timestamp: (id >> Self::TIMESTAMP_SHIFT) as i64 + epoch,
+            worker_id: ((id >> worker_shift) as i64 & Self::WORKER_MASK) as u16,
+            sequence: ((id >> sequence_shift) as i64 & Self::SEQUENCE_MASK) as u16,
+        }
+    }
+
+    pub(crate) const fn decompose_with_epoch(id: i64, epoch: i64) -> SnowflakeDecomposed {
+        Self::decompose_ordered(id, epoch, FieldOrder::WorkerThenSequence)
+    }
+
+    pub(crate) const fn decompose_ordered(id: i64, epoch: i64, order: FieldOrder) -> SnowflakeDecomposed {
+        let (worker_shift, sequence_shift) = Self::field_shifts(order);
+        let timestamp = (id >> Self::TIMESTAMP_SHIFT) + epoch;
+        let worker_id = ((id >> worker_shift) & Self::WORKER_MASK) as u16;
+        let sequence = ((id >> sequence_shift) & Self::SEQUENCE_MASK) as u16;
+
+        SnowflakeDecomposed {
+            timestamp,
+            worker_id,
+            sequence,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raw_generate_with_explicit_time() {
+        let mut raw = RawSnowflake::<41, 10, 12>::new(1_000, 3, 5_000).unwrap();
+        let id1 = raw.generate_id(5_000).unwrap();
+        let id2 = raw.generate_id(5_000).unwrap();
+        assert!(id2 > id1);
+        assert_eq!(raw.decompose(id2), SnowflakeDecomposed { timestamp: 5_000, worker_id: 3, sequence: 1 });
+        assert!(matches!(raw.generate_id(4_999), Err(SnowflakeError::ClockMovedBackwards { by_ms: 1 })));
+    }
+
+    #[test]
+    fn test_raw_sequence_exhausted() {
+        let mut raw = RawSnowflake::<41, 10, 12>::new(0, 1, 10).unwrap();
+        for _ in 0..=0xFFF {
+            raw.generate_id(10).unwrap();
+        }
+        assert!(matches!(raw.generate_id(10), Err(SnowflakeError::SequenceExhausted)));
+        let id = raw.generate_id(11).unwrap();
+        assert_eq!(raw.decompose(id).sequence, 0);
+    }
+
+    #[test]
+    fn test_raw_generate_id_monotonic_borrows_next_millisecond() {
+        let mut raw = RawSnowflake::<41, 10, 12>::new(0, 1, 10).unwrap();
+        for _ in 0..=0xFFF {
+            raw.generate_id_monotonic(10).unwrap();
+        }
+        let borrowed = raw.generate_id_monotonic(10).unwrap();
+        assert_eq!(raw.decompose(borrowed), SnowflakeDecomposed { timestamp: 11, worker_id: 1, sequence: 0 });
+        let behind = raw.generate_id_monotonic(5).unwrap();
+        assert_eq!(raw.decompose(behind).timestamp, 11);
+        let caught_up = raw.generate_id_monotonic(12).unwrap();
+        assert_eq!(raw.decompose(caught_up), SnowflakeDecomposed { timestamp: 12, worker_id: 1, sequence: 0 });
+    }
+
+    #[test]
+    fn test_raw_generate_id_monotonic_never_borrows_past_the_timestamp_bits() {
+        let last = (1 << 41) - 1;
+        let mut raw = RawSnowflake::<41, 10, 12>::new(0, 1, last).unwrap();
+        for _ in 0..=0xFFF {
+            raw.generate_id_monotonic(last).unwrap();
+        }
+        assert_eq!(raw.generate_id_monotonic(last), Err(SnowflakeError::TimestampOverflow));
+        assert_eq!(raw.generate_id_monotonic(last + 1), Err(SnowflakeError::TimestampOverflow));
+        assert_eq!(raw.time_since_epoch, last);
+        assert_eq!(raw.generate_id_monotonic(i64::MAX), Err(SnowflakeError::TimestampOverflow));
+    }
+}
@@ -0,0 +1,96 @@
+//! In-process registry of the worker IDs used by live generators, behind the
+//! `dedup-check` feature.
+//!
+//! Generators sharing a worker ID with disjoint sequence strides never collide, so each
+//! worker ID holds the `(step, offset)` slices of the sequence in use rather than a flag.
+//! The 128-bit IDs of [`crate::extended`] never collide with the 64-bit ones, so their
+//! worker IDs are kept apart.
+
+use crate::common::{Result, SnowflakeError};
+use std::collections::BTreeMap;
+
+type Slices = BTreeMap<u16, Vec<(u16, u16)>>;
+
+type Registry = std::sync::Mutex<Slices>;
+
+static LIVE_WORKER_IDS: Registry = std::sync::Mutex::new(BTreeMap::new());
+static LIVE_EXTENDED_WORKER_IDS: Registry = std::sync::Mutex::new(BTreeMap::new());
+
+fn with_live_worker_ids<T>(registry: &Registry, f: impl FnOnce(&mut Slices) -> T) -> T {
+    let mut guard = registry.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut guard)
+}
+
+/// Whether two sequence strides hand out a common sequence number.
+///
+/// Steps divide the sequence space, a power of two, so they are powers of two themselves
+/// and the smaller one divides the larger one.
+fn overlaps((step, offset): (u16, u16), (other_step, other_offset): (u16, u16)) -> bool {
+    let step = step.min(other_step);
+    offset % step == other_offset % step
+}
+
+/// Holds a worker ID, or a slice of its sequence, in the registry until dropped.
+#[derive(Debug)]
+pub(crate) struct WorkerIdGuard {
+    registry: &'static Registry,
+    worker_id: u16,
+    slice: (u16, u16),
+}
+
+impl WorkerIdGuard {
+    /// # Errors
+    /// Returns `SnowflakeError::DuplicateWorkerId` if a live generator already uses the worker ID.
+    pub(crate) fn acquire(worker_id: u16) -> Result<Self> {
+        Self::acquire_stride(worker_id, 1, 0)
+    }
+
+    /// Like `acquire`, but only holds the sequence numbers `offset, offset + step, ...`, so
+    /// that generators with disjoint strides can share the worker ID.
+    ///
+    /// # Errors
+    /// Returns `SnowflakeError::DuplicateWorkerId` if a live generator already uses the worker ID
+    /// with an overlapping stride.
+    pub(crate) fn acquire_stride(worker_id: u16, step: u16, offset: u16) -> Result<Self> {
+        Self::acquire_in(&LIVE_WORKER_IDS, worker_id, (step, offset))
+    }
+
+    /// Like `acquire`, for the 128-bit generators of [`crate::extended`].
+    ///
+    /// # Errors
+    /// Returns `SnowflakeError::DuplicateWorkerId` if a live 128-bit generator already uses the worker ID.
+    pub(crate) fn acquire_extended(worker_id: u16) -> Result<Self> {
+        Self::acquire_in(&LIVE_EXTENDED_WORKER_IDS, worker_id, (1, 0))
+    }
+
+    fn acquire_in(registry: &'static Registry, worker_id: u16, slice: (u16, u16)) -> Result<Self> {
+        let acquired = with_live_worker_ids(registry, |ids| {
+            let slices = ids.entry(worker_id).or_default();
+            if slices.iter().any(|&other| overlaps(slice, other)) {
+                return false;
+            }
+            slices.push(slice);
+            true
+        });
+        if acquired {
+            Ok(Self { registry, worker_id, slice })
+        } else {
+            Err(SnowflakeError::DuplicateWorkerId)
+        }
+    }
+}
+
+impl Drop for WorkerIdGuard {
+    fn drop(&mut self) {
+        with_live_worker_ids(self.registry, |ids| {
+            if let Some(slices) = ids.get_mut(&self.worker_id) {
+                if let Some(i) = slices.iter().position(|&slice| slice == self.slice) {
+                    slices.swap_remove(i);
+                }
+                if slices.is_empty() {
+                    ids.remove(&self.worker_id);
+                }
+            }
+        });
+    }
+}
@@ -1,9 +1,11 @@
 pub mod sync_generator {
-    use crate::common::SnowflakeState as Snowflake;
-    use crate::common::Result;
+    use crate::common::DefaultSnowflake as Snowflake;
+    use crate::common::{ClockSource, Result, SystemClock};
+    use crate::id::SnowflakeId;
 
-    pub struct SnowflakeGenerator {
-        inner: std::rc::Rc<std::cell::RefCell<Snowflake>>,
+    #[must_use]
+    pub struct SnowflakeGenerator<C: ClockSource = SystemClock> {
+        inner: std::rc::Rc<std::cell::RefCell<Snowflake<C>>>,
     }
 
     impl SnowflakeGenerator {
@@ -14,55 +16,284 @@ pub mod sync_generator {
         /// * `worker_id` - The worker ID (0-1023).
         /// # Errors
         /// Returns `SnowflakeError::WorkerIdOutOfRange` if the worker_id is out of range.
-        /// 
-        /// # Panics
-        /// Panics if the epoch is set in the future relative to the current system time.
+        /// Returns `SnowflakeError::EpochInFuture` if the epoch is set in the future relative to the current system time.
+        /// Returns `SnowflakeError::SystemTimeError` if the system time is before the Unix epoch.
         pub fn new(epoch: i64, worker_id: u16) -> Result<Self> {
             Ok(Self {
                 inner: std::rc::Rc::new(std::cell::RefCell::new(Snowflake::new(epoch, worker_id)?)),
             })
         }
 
+        /// Create a generator with epoch 0 and worker ID 0, for tests and examples.
+        /// 
+        /// # Panics
+        /// Panics if the system time is before the Unix epoch.
+        /// With the `dedup-check` feature, panics if another live generator uses worker ID 0.
+        pub fn quick() -> Self {
+            Self::new(0, 0).expect("epoch 0 and worker ID 0 are valid")
+        }
+
+        /// Create a new synchronous Snowflake ID generator using the Twitter-style
+        /// split of the worker bits into a datacenter ID and a worker ID.
+        /// 
+        /// # Arguments
+        /// * `epoch` - The custom epoch timestamp in milliseconds.
+        /// * `datacenter_id` - The datacenter ID (0-31).
+        /// * `worker_id` - The worker ID within the datacenter (0-31).
+        /// # Errors
+        /// Returns `SnowflakeError::DatacenterIdOutOfRange` or `SnowflakeError::WorkerIdOutOfRange`
+        /// if either ID is out of range.
+        /// Returns `SnowflakeError::EpochInFuture` if the epoch is set in the future relative to the current system time.
+        /// Returns `SnowflakeError::SystemTimeError` if the system time is before the Unix epoch.
+        pub fn with_datacenter(epoch: i64, datacenter_id: u8, worker_id: u8) -> Result<Self> {
+            Ok(Self {
+                inner: std::rc::Rc::new(std::cell::RefCell::new(Snowflake::with_datacenter(epoch, datacenter_id, worker_id)?)),
+            })
+        }
+    }
+
+    impl<C: ClockSource> SnowflakeGenerator<C> {
+        /// Create a new synchronous Snowflake ID generator driven by a custom clock.
+        /// 
+        /// # Arguments
+        /// * `epoch` - The custom epoch timestamp in milliseconds.
+        /// * `worker_id` - The worker ID (0-1023).
+        /// * `clock` - The clock used to read the current time.
+        /// # Errors
+        /// Returns `SnowflakeError::WorkerIdOutOfRange` if the worker_id is out of range.
+        /// Returns `SnowflakeError::EpochInFuture` if the epoch is set in the future relative to the clock's current time.
+        pub fn with_clock(epoch: i64, worker_id: u16, clock: C) -> Result<Self> {
+            Ok(Self {
+                inner: std::rc::Rc::new(std::cell::RefCell::new(Snowflake::with_clock(epoch, worker_id, clock)?)),
+            })
+        }
+
+        pub(crate) fn from_state(state: Snowflake<C>) -> Self {
+            Self {
+                inner: std::rc::Rc::new(std::cell::RefCell::new(state)),
+            }
+        }
+
         /// Generate a new Snowflake ID.
+        #[must_use]
         pub fn generate_id(&self) -> i64 {
             let mut guard = self.inner.borrow_mut();
             guard.generate_id()
         }
 
+        /// Generate a new Snowflake ID using all 64 bits, for consumers storing IDs unsigned.
+        ///
+        /// See [`RawSnowflake::generate_id_u64`](crate::raw::RawSnowflake::generate_id_u64)
+        /// for how it relates to the i64 IDs, and decompose it with [`crate::decompose_u64`].
+        #[must_use]
+        pub fn generate_id_u64(&self) -> u64 {
+            self.inner.borrow_mut().generate_id_u64()
+        }
+
+        /// Fill `buf` with monotonic Snowflake IDs without allocating.
+        pub fn generate_into(&self, buf: &mut [i64]) {
+            let mut guard = self.inner.borrow_mut();
+            buf.iter_mut().for_each(|slot| *slot = guard.generate_id());
+        }
+
+        /// Generate a new Snowflake ID, returning an error instead of waiting with
+        /// `BackoffStrategy::Error`.
+        ///
+        /// # Errors
+        /// See `SnowflakeState::generate_id_with_backoff`.
+        pub fn generate_id_with_backoff(&self) -> Result<i64> {
+            self.inner.borrow_mut().generate_id_with_backoff()
+        }
+
+        /// Set what the generator does when it has to wait for the clock.
+        pub fn set_backoff_strategy(&self, backoff: crate::common::BackoffStrategy) {
+            self.inner.borrow_mut().set_backoff_strategy(backoff);
+        }
+
+        /// Set how leaps of the clock are detected and smoothed.
+        /// See [`ForwardJumpPolicy`](crate::ForwardJumpPolicy).
+        pub fn set_forward_jump_policy(&self, policy: crate::common::ForwardJumpPolicy) {
+            self.inner.borrow_mut().set_forward_jump_policy(policy);
+        }
+
+        /// Generate a new strongly-typed Snowflake ID.
+        #[must_use]
+        pub fn generate(&self) -> SnowflakeId {
+            SnowflakeId::new(self.generate_id())
+        }
+
+        /// Generate a new Snowflake ID formatted as a decimal string.
+        #[must_use]
+        pub fn generate_id_string(&self) -> String {
+            self.generate_id().to_string()
+        }
+
+        /// Generate a new Snowflake ID encoded as a Base62 string.
+        #[must_use]
+        pub fn generate_id_base62_string(&self) -> String {
+            crate::encoding::encode_base62(self.generate_id())
+        }
+
+        /// Try to generate a new Snowflake ID without blocking.
+        ///
+        /// # Errors
+        /// Returns `SnowflakeError::SequenceExhausted` if the sequence for the current millisecond is used up.
+        /// Returns `SnowflakeError::ClockMovedBackwards` if the clock went backwards since the last ID.
+        /// Returns `SnowflakeError::TimestampOverflow` once the time since the epoch no longer fits the timestamp bits.
+        pub fn try_generate_id(&self) -> Result<i64> {
+            self.inner.borrow_mut().try_generate_id()
+        }
+
+        /// Generate an ID for an explicit past timestamp, for backfilling historical records.
+        ///
+        /// # Errors
+        /// Returns `SnowflakeError::TimestampBeforeEpoch` if the timestamp is before the epoch.
+        /// Returns `SnowflakeError::TimestampNotInPast` if the timestamp is not in the past.
+        /// Returns `SnowflakeError::SequenceExhausted` if the sequence for that millisecond is used up.
+        pub fn generate_id_at(&self, timestamp_ms: i64) -> Result<i64> {
+            self.inner.borrow_mut().generate_id_at(timestamp_ms)
+        }
+
+        /// The ID the next call to `generate_id` would return, without consuming it.
+        #[must_use]
+        pub fn peek_id(&self) -> i64 {
+            self.inner.borrow().peek_id()
+        }
+
+        /// How many more IDs fit the current millisecond before generating has to wait.
+        pub fn remaining_in_window(&self) -> u16 {
+            self.inner.borrow().remaining_in_window()
+        }
+
+        /// The custom epoch in milliseconds, e.g. for [`crate::decompose_snowflake`].
+        pub fn epoch(&self) -> i64 {
+            self.inner.borrow().epoch
+        }
+
         /// Decompose a Snowflake ID into its components.
+        #[must_use]
         pub fn decompose(&self, id: i64) -> crate::common::SnowflakeDecomposed {
             self.inner.borrow().decompose(id)
         }
+
+        /// Like `decompose`, but rejects negative IDs and timestamps overflowing with the epoch.
+        ///
+        /// # Errors
+        /// Fails like [`crate::decompose_snowflake`].
+        pub fn try_decompose(&self, id: i64) -> Result<crate::common::SnowflakeDecomposed> {
+            self.inner.borrow().try_decompose(id)
+        }
+
+        /// Counters of the IDs generated so far, and the current drift from the clock.
+        pub fn stats(&self) -> crate::common::SnowflakeStats {
+            self.inner.borrow().stats()
+        }
+
+        /// How far the timestamp of the last ID is ahead of the clock, in milliseconds.
+        /// See `SnowflakeState::drift_ms`.
+        pub fn drift_ms(&self) -> i64 {
+            self.inner.borrow().drift_ms()
+        }
+
+        /// Take back the state, e.g. to save it with
+        /// [`SnowflakeState::to_bytes`](crate::SnowflakeState::to_bytes) on shutdown.
+        ///
+        /// # Errors
+        /// Returns the generator unchanged if it has been cloned and a clone is still alive.
+        pub fn try_into_inner(self) -> std::result::Result<Snowflake<C>, Self> {
+            std::rc::Rc::try_unwrap(self.inner)
+                .map(std::cell::RefCell::into_inner)
+                .map_err(|inner| Self { inner })
+        }
+
+        /// An infinite iterator of new Snowflake IDs.
+        ///
+        /// The generator is only borrowed while each ID is produced, so it can still be
+        /// used directly while the iterator is alive.
+        pub fn iter(&self) -> Iter<'_, C> {
+            Iter { generator: self }
+        }
+    }
+
+    /// An infinite iterator of Snowflake IDs, created by [`SnowflakeGenerator::iter`].
+    pub struct Iter<'a, C: ClockSource = SystemClock> {
+        generator: &'a SnowflakeGenerator<C>,
+    }
+
+    impl<C: ClockSource> Iterator for Iter<'_, C> {
+        type Item = i64;
+
+        fn next(&mut self) -> Option<i64> {
+            Some(self.generator.generate_id())
+        }
+    }
+
+    impl<'a, C: ClockSource> IntoIterator for &'a SnowflakeGenerator<C> {
+        type Item = i64;
+        type IntoIter = Iter<'a, C>;
+
+        fn into_iter(self) -> Iter<'a, C> {
+            self.iter()
+        }
     }
 
-    impl Clone for SnowflakeGenerator {
+    impl<C: ClockSource> Clone for SnowflakeGenerator<C> {
         fn clone(&self) -> Self {
             Self {
                 inner: self.inner.clone(),
             }
         }
     }
+
+    impl Default for SnowflakeGenerator {
+        fn default() -> Self {
+            Self::quick()
+        }
+    }
+
+    impl<C: ClockSource> crate::common::SnowflakeSource for SnowflakeGenerator<C> {
+        fn generate_id(&self) -> i64 {
+            SnowflakeGenerator::generate_id(self)
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::common::SnowflakeState as Snowflake;
+    use crate::common::DefaultSnowflake as Snowflake;
     #[test]
     fn test_snowflake_id_generation() {
+        let _serial = crate::tests::serial();
         let mut snowflake = Snowflake::new(0, 1).unwrap();
         let id1 = snowflake.generate_id();
         let id2 = snowflake.generate_id();
         assert!(id2 > id1);
     }
 
+    #[test]
+    fn test_iter_interleaved_with_generate_id() {
+        let _serial = crate::tests::serial();
+        let generator = sync_generator::SnowflakeGenerator::new(0, 1).unwrap();
+        let mut iter = generator.iter();
+        let id1 = iter.next().unwrap();
+        let id2 = generator.generate_id();
+        let id3 = iter.next().unwrap();
+        assert!(id1 < id2 && id2 < id3);
+
+        let ids: Vec<i64> = generator.iter().take(100).collect();
+        assert_eq!(ids.len(), 100);
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    }
+
     #[test]
     fn bench_snowflake_id_generation() {
+        let _serial = crate::tests::serial();
         let snowflake = sync_generator::SnowflakeGenerator::new(0, 1).unwrap();
         let ids = 1_000_000;
         let time = std::time::Instant::now();
         for _ in 0..ids {
-            snowflake.generate_id();
+            let _ = snowflake.generate_id();
         }
         let elapsed = time.elapsed();
         println!(
@@ -0,0 +1,119 @@
+//! IDs in the Sonyflake layout, for interoperating with services using
+//! [Sonyflake](https://github.com/sony/sonyflake).
+//!
+//! From most-significant bit to least, a Sonyflake ID has 1 unused sign bit, 39 bits of
+//! time in units of 10 ms since the start time, 8 sequence bits and 16 machine ID bits.
+//! Unlike Snowflake IDs, the sequence is above the machine ID.
+
+use crate::common::{ClockSource, Result, SnowflakeDecomposed, SnowflakeError, SystemClock};
+use crate::raw::RawSnowflake;
+
+/// The default start time of Sonyflake, 2014-09-01T00:00:00Z, in milliseconds.
+pub const DEFAULT_START_TIME: i64 = 1_409_529_600_000;
+
+const TIME_UNIT_MS: i64 = 10;
+const SEQUENCE_BITS: u32 = 8;
+const MACHINE_ID_BITS: u32 = 16;
+
+/// A Sonyflake ID generator.
+///
+/// The sequence and clock handling is that of a [`RawSnowflake`] with a 39/16/8 layout
+/// counting time in 10 ms units, whose IDs are repacked into the Sonyflake bit order.
+#[must_use]
+pub struct Sonyflake<C: ClockSource = SystemClock> {
+    raw: RawSnowflake<39, 16, 8>,
+    clock: C,
+}
+
+impl Sonyflake {
+    /// Create a new Sonyflake ID generator.
+    ///
+    /// # Arguments
+    /// * `start_time` - The start time in milliseconds, usually [`DEFAULT_START_TIME`].
+    /// * `machine_id` - The machine ID, any 16-bit value.
+    /// # Errors
+    /// Returns `SnowflakeError::EpochInFuture` if the start time is in the future.
+    /// Returns `SnowflakeError::SystemTimeError` if the system time is before the Unix epoch.
+    pub fn new(start_time: i64, machine_id: u16) -> Result<Self> {
+        Self::with_clock(start_time, machine_id, SystemClock::new()?)
+    }
+}
+
+impl<C: ClockSource> Sonyflake<C> {
+    /// Create a new Sonyflake ID generator driven by a custom clock.
+    ///
+    /// # Errors
+    /// Returns `SnowflakeError::EpochInFuture` if the start time is in the future.
+    pub fn with_clock(start_time: i64, machine_id: u16, clock: C) -> Result<Self> {
+        let raw = RawSnowflake::new(start_time / TIME_UNIT_MS, machine_id, clock.now_millis() / TIME_UNIT_MS)?;
+        Ok(Self { raw, clock })
+    }
+
+    /// Generate a new Sonyflake ID, sleeping until the next 10 ms unit when the 256
+    /// sequence numbers of the current one are used up or the clock moved backwards.
+    ///
+    /// # Panics
+    /// Panics once the 39 time bits are exhausted, about 174 years after the start time.
+    #[must_use]
+    pub fn generate_id(&mut self) -> i64 {
+        loop {
+            match self.try_generate_id() {
+                Ok(id) => return id,
+                Err(SnowflakeError::ClockMovedBackwards { by_ms }) => {
+                    std::thread::sleep(std::time::Duration::from_millis(by_ms as u64))
+                }
+                Err(SnowflakeError::SequenceExhausted) => std::thread::sleep(std::time::Duration::from_millis(1)),
+                Err(e) => panic!("Cannot generate an ID: {}", e),
+            }
+        }
+    }
+
+    /// Generate a new Sonyflake ID without blocking.
+    ///
+    /// # Errors
+    /// Returns `SnowflakeError::SequenceExhausted` if the sequence for the current 10 ms is used up.
+    /// Returns `SnowflakeError::ClockMovedBackwards` if the clock went backwards since the
+    /// last ID, by a multiple of 10 ms.
+    /// Returns `SnowflakeError::TimestampOverflow` once the 39 time bits are exhausted.
+    pub fn try_generate_id(&mut self) -> Result<i64> {
+        let id = self.raw.generate_id(self.clock.now_millis() / TIME_UNIT_MS).map_err(|e| match e {
+            SnowflakeError::ClockMovedBackwards { by_ms } => SnowflakeError::ClockMovedBackwards { by_ms: by_ms * TIME_UNIT_MS },
+            e => e,
+        })?;
+        let decomposed = self.raw.decompose(id);
+        let time = (decomposed.timestamp - self.raw.epoch) << (SEQUENCE_BITS + MACHINE_ID_BITS);
+        Ok(time | (decomposed.sequence as i64) << MACHINE_ID_BITS | decomposed.worker_id as i64)
+    }
+
+    /// Decompose a Sonyflake ID generated with the same start time.
+    #[must_use]
+    pub fn decompose(&self, id: i64) -> SnowflakeDecomposed {
+        decompose_units(id, self.raw.epoch)
+    }
+}
+
+/// Decompose a Sonyflake ID into its components, with the timestamp in milliseconds
+/// (a multiple of 10) and the machine ID as `worker_id`.
+///
+/// # Errors
+/// Returns `SnowflakeError::NegativeId` if the sign bit is set.
+/// Returns `SnowflakeError::TimestampOverflow` if the timestamp overflows once the start
+/// time is added.
+pub fn decompose_sonyflake(id: i64, start_time: i64) -> Result<SnowflakeDecomposed> {
+    if id < 0 {
+        return Err(SnowflakeError::NegativeId);
+    }
+    let units = (id >> (SEQUENCE_BITS + MACHINE_ID_BITS)).checked_add(start_time / TIME_UNIT_MS);
+    if units.and_then(|units| units.checked_mul(TIME_UNIT_MS)).is_none() {
+        return Err(SnowflakeError::TimestampOverflow);
+    }
+    Ok(decompose_units(id, start_time / TIME_UNIT_MS))
+}
+
+fn decompose_units(id: i64, start_units: i64) -> SnowflakeDecomposed {
+    SnowflakeDecomposed {
+        timestamp: ((id >> (SEQUENCE_BITS + MACHINE_ID_BITS)) + start_units) * TIME_UNIT_MS,
+        worker_id: id as u16,
+        sequence: (id >> MACHINE_ID_BITS) as u8 as u16,
+    }
+}
@@ -0,0 +1,90 @@
+//! Tenant-scoped IDs, encoding a logical tenant in the high worker bits and the node
+//! serving it in the remaining low worker bits.
+//!
+//! This generalizes the Twitter-style datacenter split to any number of tenant bits and
+//! any worker width of the configurable layout.
+
+use crate::common::{Result, SnowflakeDecomposed, SnowflakeError};
+
+/// A logical tenant, stored in the high worker bits of an ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TenantId(u16);
+
+impl TenantId {
+    /// Wrap a raw tenant ID. Whether it fits is checked against a [`TenantLayout`].
+    #[must_use]
+    pub const fn new(tenant_id: u16) -> Self {
+        Self(tenant_id)
+    }
+
+    /// The raw value of the tenant ID.
+    pub const fn get(&self) -> u16 {
+        self.0
+    }
+}
+
+impl core::fmt::Display for TenantId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// How the `WORKER_BITS` of a layout are split between a tenant and a node.
+///
+/// With the default 10 worker bits and 4 tenant bits, there are 16 tenants, each served
+/// by up to 64 nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TenantLayout<const WORKER_BITS: usize = 10> {
+    tenant_bits: usize,
+}
+
+impl<const WORKER_BITS: usize> TenantLayout<WORKER_BITS> {
+    /// # Errors
+    /// Returns `SnowflakeError::InvalidTenantLayout` if `tenant_bits` is 0 or exceeds the
+    /// worker bits.
+    pub const fn new(tenant_bits: usize) -> Result<Self> {
+        if tenant_bits == 0 || tenant_bits > WORKER_BITS {
+            return Err(SnowflakeError::InvalidTenantLayout);
+        }
+        Ok(Self { tenant_bits })
+    }
+
+    const fn node_bits(&self) -> usize {
+        WORKER_BITS - self.tenant_bits
+    }
+
+    /// The largest tenant ID of the layout.
+    pub const fn max_tenant(&self) -> TenantId {
+        TenantId(((1u32 << self.tenant_bits) - 1) as u16)
+    }
+
+    /// The largest node ID of the layout.
+    pub const fn max_node_id(&self) -> u16 {
+        ((1u32 << self.node_bits()) - 1) as u16
+    }
+
+    /// Pack a tenant and the ID of the node serving it into a worker ID.
+    ///
+    /// # Errors
+    /// Returns `SnowflakeError::TenantIdOutOfRange` if the tenant does not fit the tenant bits.
+    /// Returns `SnowflakeError::WorkerIdOutOfRange` if the node ID does not fit the remaining bits.
+    pub const fn worker_id(&self, tenant: TenantId, node_id: u16) -> Result<u16> {
+        if tenant.0 > self.max_tenant().0 {
+            return Err(SnowflakeError::TenantIdOutOfRange);
+        }
+        if node_id > self.max_node_id() {
+            return Err(SnowflakeError::WorkerIdOutOfRange);
+        }
+        Ok(((tenant.0 as u32) << self.node_bits()) as u16 | node_id)
+    }
+
+    /// The tenant of a decomposed ID.
+    pub const fn tenant(&self, decomposed: &SnowflakeDecomposed) -> TenantId {
+        TenantId((decomposed.worker_id as u32 >> self.node_bits()) as u16)
+    }
+
+    /// The ID of the node that generated a decomposed ID, within its tenant.
+    pub const fn node_id(&self, decomposed: &SnowflakeDecomposed) -> u16 {
+        decomposed.worker_id & self.max_node_id()
+    }
+}
@@ -0,0 +1,65 @@
+//! Helpers for the test suites of downstream crates, behind the `test-util` feature.
+
+use crate::common::{Result, SnowflakeDecomposed, SnowflakeError};
+use crate::raw::RawSnowflake;
+
+/// A Snowflake ID generator producing the same IDs on every run, e.g. for golden-file tests.
+///
+/// It is the default 41/10/12 layout driven by its own clock instead of the wall-clock.
+/// That clock starts at a fixed timestamp and only moves forward when advanced explicitly
+/// or when the sequence of the current millisecond is used up, so the IDs never depend on
+/// the time of the run or on how fast they are generated.
+#[must_use]
+pub struct DeterministicGenerator {
+    raw: RawSnowflake<41, 10, 12>,
+    now: i64,
+}
+
+impl DeterministicGenerator {
+    /// Create a new deterministic generator.
+    ///
+    /// # Arguments
+    /// * `epoch` - The custom epoch timestamp in milliseconds.
+    /// * `worker_id` - The worker ID (0-1023).
+    /// * `start_millis` - The timestamp of the first ID, in milliseconds.
+    /// # Errors
+    /// Returns `SnowflakeError::WorkerIdOutOfRange` if the worker_id is out of range.
+    /// Returns `SnowflakeError::EpochInFuture` if `start_millis` is before the epoch.
+    pub fn new(epoch: i64, worker_id: u16, start_millis: i64) -> Result<Self> {
+        Ok(Self {
+            raw: RawSnowflake::new(epoch, worker_id, start_millis)?,
+            now: start_millis,
+        })
+    }
+
+    /// Generate the next ID, moving to the next millisecond once the sequence is used up.
+    ///
+    /// # Panics
+    /// Panics once the time since the epoch no longer fits the 41 timestamp bits.
+    #[must_use]
+    pub fn generate_id(&mut self) -> i64 {
+        loop {
+            match self.raw.generate_id(self.now) {
+                Ok(id) => return id,
+                Err(SnowflakeError::SequenceExhausted) => self.now += 1,
+                Err(e) => panic!("Cannot generate an ID: {}", e),
+            }
+        }
+    }
+
+    /// Move the clock forward by `ms` milliseconds.
+    pub fn advance(&mut self, ms: u32) {
+        self.now += i64::from(ms);
+    }
+
+    /// The current time of the generator's clock, in milliseconds.
+    pub fn now_millis(&self) -> i64 {
+        self.now
+    }
+
+    /// Decompose an ID generated by this generator.
+    #[must_use]
+    pub fn decompose(&self, id: i64) -> SnowflakeDecomposed {
+        self.raw.decompose(id)
+    }
+}
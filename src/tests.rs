@@ -1,9 +1,71 @@
 use crate::single_thread::sync_generator::SnowflakeGenerator as STSG;
 use crate::multi_thread::sync_generator::SnowflakeGenerator as MTSG;
+#[cfg(feature = "tokio")]
 use crate::multi_thread::async_generator::SnowflakeGenerator as MTAG;
+use crate::multi_thread::atomic_generator::SnowflakeGenerator as MTAtG;
+use crate::common::{DefaultSnowflake, SnowflakeState};
+use crate::{ClockSource, SnowflakeError};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// A manually driven clock for deterministic tests.
+#[derive(Clone)]
+pub struct MockClock {
+    now: Arc<AtomicI64>,
+    /// How far the reported time lags behind `now`, until corrected by `resync`.
+    lag: Arc<AtomicI64>,
+}
+
+impl MockClock {
+    pub fn new(now: i64) -> Self {
+        Self {
+            now: Arc::new(AtomicI64::new(now)),
+            lag: Arc::new(AtomicI64::new(0)),
+        }
+    }
+
+    /// Let the reported time fall behind the wall-clock, like an `Instant` during suspend.
+    pub fn drift(&self, ms: i64) {
+        self.lag.fetch_add(ms, Ordering::SeqCst);
+    }
+
+    pub fn set(&self, now: i64) {
+        self.now.store(now, Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, ms: i64) {
+        self.now.fetch_add(ms, Ordering::SeqCst);
+    }
+}
+
+impl ClockSource for MockClock {
+    fn now_millis(&self) -> i64 {
+        self.now.load(Ordering::SeqCst) - self.lag.load(Ordering::SeqCst)
+    }
+
+    fn resync(&mut self, threshold_ms: i64) -> crate::Result<()> {
+        if self.lag.load(Ordering::SeqCst) > threshold_ms {
+            self.lag.store(0, Ordering::SeqCst);
+        }
+        Ok(())
+    }
+}
+
+/// The dedup-check registry is process-wide and most tests reuse the same worker IDs, so
+/// with the feature on, the tests holding generators run one at a time.
+static SERIAL: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+pub(crate) fn serial() -> Option<tokio::sync::MutexGuard<'static, ()>> {
+    cfg!(feature = "dedup-check").then(|| SERIAL.blocking_lock())
+}
+
+pub(crate) async fn serial_async() -> Option<tokio::sync::MutexGuard<'static, ()>> {
+    if cfg!(feature = "dedup-check") { Some(SERIAL.lock().await) } else { None }
+}
 
 #[test]
 fn test_single_thread_snowflake_id_generation() {
+    let _serial = serial();
     let generator = STSG::new(0, 1).unwrap();
     let id1 = generator.generate_id();
     let id2 = generator.generate_id();
@@ -14,8 +76,10 @@ fn test_single_thread_snowflake_id_generation() {
     assert!(id2 > id1);
 }
 
+#[cfg(feature = "tokio")]
 #[tokio::test]
 async fn test_multi_thread_async_snowflake_id_generation() {
+    let _serial = serial_async().await;
     let generator = MTAG::new(0, 1).unwrap();
 
     let handle1 = {
@@ -44,6 +108,7 @@ async fn test_multi_thread_async_snowflake_id_generation() {
 #[test]
 fn test_multi_thread_sync_snowflake_id_generation() {
     use std::thread;
+    let _serial = serial();
     let generator = MTSG::new(0, 1).unwrap();
 
     let handle1 = {
@@ -71,64 +136,2700 @@ fn test_multi_thread_sync_snowflake_id_generation() {
 }
 
 #[test]
-fn bench_single_thread_snowflake_id_generation() {
+fn test_try_generate_id_sequence_exhausted_keeps_state() {
+    let _serial = serial();
+    let mut state = DefaultSnowflake::new(0, 1).unwrap();
+    loop {
+        let last_id = state.generate_id();
+        state.sequence = 0x1000;
+        let before = (state.time_since_epoch, state.sequence);
+        match state.try_generate_id() {
+            Err(SnowflakeError::SequenceExhausted) => {
+                assert_eq!(before, (state.time_since_epoch, state.sequence));
+                let id = state.generate_id();
+                assert!(id > last_id);
+                break;
+            }
+            // The millisecond rolled over between the two calls, try again.
+            Ok(_) => continue,
+            Err(e) => panic!("unexpected error: {}", e),
+        }
+    }
+}
+
+#[test]
+fn test_clock_moved_backwards_is_detected() {
+    let _serial = serial();
+    let mut state = DefaultSnowflake::new(0, 1).unwrap();
+    let _ = state.generate_id();
+    // Pretend the last ID was generated one second in the future.
+    state.time_since_epoch += 1000;
+    let before = (state.time_since_epoch, state.sequence);
+    match state.try_generate_id() {
+        Err(SnowflakeError::ClockMovedBackwards { by_ms }) => assert!(by_ms > 0 && by_ms <= 1000),
+        other => panic!("expected ClockMovedBackwards, got {:?}", other),
+    }
+    assert_eq!(before, (state.time_since_epoch, state.sequence));
+}
+
+#[test]
+fn test_generate_id_stays_monotonic_when_clock_moves_backwards() {
+    let _serial = serial();
+    let mut state = DefaultSnowflake::new(0, 1).unwrap();
+    let _ = state.generate_id();
+    state.time_since_epoch += 20;
+    let last_time = state.time_since_epoch;
+    let id = state.generate_id();
+    assert!(state.decompose(id).timestamp >= last_time);
+}
+
+#[test]
+fn test_mock_clock_sequence_rollover() {
+    let _serial = serial();
+    let clock = MockClock::new(1_000);
+    let mut state = DefaultSnowflake::with_clock(0, 1, clock.clone()).unwrap();
+    for expected in 0..=0xFFF {
+        let id = state.try_generate_id().unwrap();
+        assert_eq!(state.decompose(id).sequence, expected);
+    }
+    assert!(matches!(state.try_generate_id(), Err(SnowflakeError::SequenceExhausted)));
+
+    clock.advance(1);
+    let id = state.try_generate_id().unwrap();
+    let decomposed = state.decompose(id);
+    assert_eq!(decomposed.timestamp, 1_001);
+    assert_eq!(decomposed.sequence, 0);
+}
+
+#[test]
+fn test_mock_clock_moved_backwards() {
+    let _serial = serial();
+    let clock = MockClock::new(1_000);
+    let generator = STSG::with_clock(0, 1, clock.clone()).unwrap();
+    let id1 = generator.try_generate_id().unwrap();
+    clock.set(990);
+    assert!(matches!(
+        generator.try_generate_id(),
+        Err(SnowflakeError::ClockMovedBackwards { by_ms: 10 })
+    ));
+    clock.set(1_000);
+    let id2 = generator.try_generate_id().unwrap();
+    assert!(id2 > id1);
+}
+
+#[test]
+fn test_datacenter_split() {
+    let _serial = serial();
+    let generator = MTSG::with_datacenter(0, 3, 7).unwrap();
+    let id = generator.generate_id();
+    assert_eq!((id >> 17) & 0x1F, 3);
+    assert_eq!((id >> 12) & 0x1F, 7);
+    let decomposed = generator.decompose(id);
+    assert_eq!(decomposed.worker_id, (3 << 5) | 7);
+    assert_eq!(decomposed.datacenter_id(), 3);
+    assert_eq!(decomposed.datacenter_worker_id(), 7);
+
+    assert!(matches!(STSG::with_datacenter(0, 32, 0), Err(SnowflakeError::DatacenterIdOutOfRange)));
+    assert!(matches!(STSG::with_datacenter(0, 0, 32), Err(SnowflakeError::WorkerIdOutOfRange)));
+}
+
+#[test]
+fn test_try_decompose_rejects_negative_ids() {
+    let _serial = serial();
+    let single = STSG::new(1_000, 3).unwrap();
+    let id = single.generate_id();
+    assert_eq!(single.try_decompose(id), Ok(single.decompose(id)));
+    assert_eq!(single.try_decompose(-id), Err(SnowflakeError::NegativeId));
+    // Release worker 3 before reusing it, as `dedup-check` rejects two live generators.
+    drop(single);
+
+    let multi = MTSG::new(1_000, 3).unwrap();
+    assert_eq!(multi.try_decompose(id), Ok(multi.decompose(id)));
+    assert_eq!(multi.try_decompose(-1), Err(SnowflakeError::NegativeId));
+    assert_eq!(MTAtG::quick().try_decompose(i64::MIN), Err(SnowflakeError::NegativeId));
+
+    let state = DefaultSnowflake::with_clock(i64::MAX - 10, 0, MockClock::new(i64::MAX)).unwrap();
+    assert_eq!(state.try_decompose(1 << 30), Err(SnowflakeError::TimestampOverflow));
+    let state = SnowflakeState::<39, 14, 10, MockClock>::with_clock(0, 5, MockClock::new(10)).unwrap();
+    assert_eq!(state.try_decompose(-5), Err(SnowflakeError::NegativeId));
+}
+
+#[test]
+fn test_errors_compare_equal() {
+    let _serial = serial();
+    assert_eq!(MTSG::new(0, 1024).err(), Some(SnowflakeError::WorkerIdOutOfRange));
+    assert_eq!(crate::pack_datacenter(1, 32), Err(SnowflakeError::WorkerIdOutOfRange));
+    let error = crate::decompose_snowflake(-1, 0).unwrap_err();
+    assert_eq!(error.clone(), SnowflakeError::NegativeId);
+    assert_ne!(
+        SnowflakeError::WorkerIdMismatch { expected: 1, found: 2 },
+        SnowflakeError::WorkerIdMismatch { expected: 2, found: 1 }
+    );
+    assert_eq!(crate::encoding::decode_base62("!"), Err(crate::SnowflakeDecodeError::InvalidBase62));
+}
+
+#[test]
+fn test_sonyflake_round_trip() {
+    use crate::sonyflake::{Sonyflake, DEFAULT_START_TIME};
+    let clock = MockClock::new(DEFAULT_START_TIME + 12_345_672);
+    let mut generator = Sonyflake::with_clock(DEFAULT_START_TIME, 0xBEEF, clock.clone()).unwrap();
+    let ids: Vec<i64> = (0..256).map(|_| generator.try_generate_id().unwrap()).collect();
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    let last = generator.decompose(ids[255]);
+    assert_eq!(last, crate::SnowflakeDecomposed { timestamp: DEFAULT_START_TIME + 12_345_670, worker_id: 0xBEEF, sequence: 255 });
+    assert_eq!(generator.try_generate_id(), Err(SnowflakeError::SequenceExhausted));
+
+    // Still in the same 10 ms unit.
+    clock.advance(2);
+    assert_eq!(generator.try_generate_id(), Err(SnowflakeError::SequenceExhausted));
+    clock.advance(8);
+    let next = generator.generate_id();
+    assert_eq!(generator.decompose(next), crate::SnowflakeDecomposed { timestamp: DEFAULT_START_TIME + 12_345_680, worker_id: 0xBEEF, sequence: 0 });
+    assert!(next > ids[255]);
+    clock.advance(-20);
+    assert_eq!(generator.try_generate_id(), Err(SnowflakeError::ClockMovedBackwards { by_ms: 20 }));
+
+    let mut generator = Sonyflake::new(DEFAULT_START_TIME, 1).unwrap();
+    let id = generator.generate_id();
+    let decomposed = crate::sonyflake::decompose_sonyflake(id, DEFAULT_START_TIME).unwrap();
+    assert_eq!(decomposed.worker_id, 1);
+    assert_eq!(decomposed.timestamp % 10, 0);
+}
+
+#[test]
+fn test_sonyflake_known_id() {
+    use crate::sonyflake::{decompose_sonyflake, DEFAULT_START_TIME};
+    // 0x12D687 time units, sequence 5, machine ID 0xABCD.
+    let id = (0x12D687 << 24) | (5 << 16) | 0xABCD;
+    let decomposed = decompose_sonyflake(id, DEFAULT_START_TIME).unwrap();
+    assert_eq!(decomposed, crate::SnowflakeDecomposed { timestamp: DEFAULT_START_TIME + 12_345_670, worker_id: 0xABCD, sequence: 5 });
+    assert_eq!(decompose_sonyflake(-1, DEFAULT_START_TIME), Err(SnowflakeError::NegativeId));
+    assert_eq!(decompose_sonyflake(i64::MAX, i64::MAX), Err(SnowflakeError::TimestampOverflow));
+}
+
+#[test]
+fn test_tenant_ids() {
+    use crate::tenant::{TenantId, TenantLayout};
+    let _serial = serial();
+    let layout = TenantLayout::new(4).unwrap();
+    let (acme, globex) = (TenantId::new(3), TenantId::new(15));
+    let mut acme_state = DefaultSnowflake::with_tenant(1_000, layout, acme, 63).unwrap();
+    let mut globex_state = DefaultSnowflake::with_tenant(1_000, layout, globex, 0).unwrap();
+    for _ in 0..10 {
+        let decomposed = crate::decompose_snowflake(acme_state.generate_id(), 1_000).unwrap();
+        assert_eq!((layout.tenant(&decomposed), layout.node_id(&decomposed)), (acme, 63));
+        let decomposed = crate::decompose_snowflake(globex_state.generate_id(), 1_000).unwrap();
+        assert_eq!((layout.tenant(&decomposed), layout.node_id(&decomposed)), (globex, 0));
+    }
+
+    assert_eq!(layout.max_tenant(), TenantId::new(15));
+    assert_eq!(layout.worker_id(TenantId::new(16), 0), Err(SnowflakeError::TenantIdOutOfRange));
+    assert_eq!(layout.worker_id(acme, 64), Err(SnowflakeError::WorkerIdOutOfRange));
+    assert_eq!(TenantLayout::<10>::new(0), Err(SnowflakeError::InvalidTenantLayout));
+    assert_eq!(TenantLayout::<10>::new(11), Err(SnowflakeError::InvalidTenantLayout));
+
+    // Every worker bit for the tenant, on a wider layout.
+    let layout = TenantLayout::<14>::new(14).unwrap();
+    let state = SnowflakeState::<39, 14, 10>::with_tenant(0, layout, TenantId::new(0x3FFF), 0).unwrap();
+    assert_eq!(layout.tenant(&state.decompose(state.peek_id())), TenantId::new(0x3FFF));
+}
+
+#[test]
+fn test_custom_bit_layout_round_trip() {
+    let _serial = serial();
+    let clock = MockClock::new(5_000);
+    let mut state = SnowflakeState::<39, 14, 10, MockClock>::with_clock(1_000, 0x3FFF, clock.clone()).unwrap();
+    for expected in 0..=0x3FF {
+        let id = state.try_generate_id().unwrap();
+        let decomposed = state.decompose(id);
+        assert_eq!(decomposed.timestamp, 5_000);
+        assert_eq!(decomposed.worker_id, 0x3FFF);
+        assert_eq!(decomposed.sequence, expected);
+    }
+    assert!(matches!(state.try_generate_id(), Err(SnowflakeError::SequenceExhausted)));
+    clock.advance(1);
+    let id = state.try_generate_id().unwrap();
+    assert_eq!(state.decompose(id).timestamp, 5_001);
+
+    assert!(matches!(
+        SnowflakeState::<39, 14, 10>::new(0, 0x4000),
+        Err(SnowflakeError::WorkerIdOutOfRange)
+    ));
+}
+
+#[test]
+fn test_epoch_exhaustion() {
+    use crate::{epoch_exhaustion_date, epoch_years_remaining};
+    let exhaustion = epoch_exhaustion_date(0, 41);
+    assert_eq!(exhaustion, 1 << 41);
+    // 2039-09-07, between 2039-01-01 and 2040-01-01.
+    assert!((2_177_452_800_000..2_208_988_800_000).contains(&exhaustion));
+    assert_eq!(epoch_exhaustion_date(1_000, 39), 1_000 + (1 << 39));
+    assert_eq!(epoch_exhaustion_date(1_000, 63), i64::MAX);
+    assert_eq!(epoch_exhaustion_date(i64::MAX - 1, 41), i64::MAX);
+
+    let years = epoch_years_remaining(0, 41).unwrap();
+    assert!(years > 0.0 && years < 70.0);
+    assert!(epoch_years_remaining(0, 30).unwrap() < 0.0);
+}
+
+#[test]
+fn test_inspect() {
+    let _serial = serial();
+    let epoch = 1_420_070_400_000;
+    let mut state = DefaultSnowflake::with_clock(epoch, 0x2A5, MockClock::new(1_704_067_200_123)).unwrap();
+    let _ = state.generate_id();
+    let id = state.generate_id();
+    let description = crate::inspect(id, epoch);
+    assert!(description.contains(&format!("ID:        {}", id)));
+    assert!(description.contains("2024-01-01T00:00:00.123Z (1704067200123 ms)"));
+    assert!(description.contains("Worker ID: 677"));
+    assert!(description.contains("Sequence:  1"));
+    assert!(description.contains(" 1010100101 000000000001"));
+
+    assert!(crate::inspect(0, 0).contains("1970-01-01T00:00:00.000Z"));
+    assert!(crate::inspect(0, -1).contains("1969-12-31T23:59:59.999Z"));
+    assert!(crate::inspect(-1, 0).contains("Invalid:   ID is negative"));
+    for timestamp in [-86_400_001, 951_782_400_000, 4_102_444_799_999, 1_704_067_200_123] {
+        let expected = chrono::DateTime::from_timestamp_millis(timestamp).unwrap().format("%Y-%m-%dT%H:%M:%S%.3fZ");
+        assert!(crate::inspect(0, timestamp).contains(&expected.to_string()));
+    }
+}
+
+#[test]
+fn test_decomposed_diff() {
+    let _serial = serial();
+    let clock = MockClock::new(1_000);
+    let mut state = DefaultSnowflake::with_clock(0, 3, clock.clone()).unwrap();
+    let _ = state.generate_id();
+    let first = state.generate_id();
+    clock.advance(25);
+    let second = state.generate_id();
+    let (first, second) = (state.decompose(first), state.decompose(second));
+
+    let delta = first.diff(&second);
+    assert_eq!(delta, crate::SnowflakeDelta { ms_delta: 25, same_worker: true, sequence_delta: -1 });
+    assert_eq!(second.diff(&first).ms_delta, -25);
+    let other_worker = crate::SnowflakeDecomposed { worker_id: 4, ..second.clone() };
+    assert!(!second.diff(&other_worker).same_worker);
+    assert_eq!(first.diff(&first), crate::SnowflakeDelta { ms_delta: 0, same_worker: true, sequence_delta: 0 });
+}
+
+#[test]
+fn test_decomposed_ordering() {
+    let _serial = serial();
+    let clock = MockClock::new(1_000);
+    let mut state = DefaultSnowflake::with_clock(0, 3, clock.clone()).unwrap();
+    let mut ids = Vec::new();
+    for ms in [1_000, 1_000, 1_001, 1_001, 1_001, 1_005] {
+        clock.set(ms);
+        ids.push(state.generate_id());
+    }
+    let decomposed: std::collections::BTreeSet<_> = ids.iter().rev().map(|&id| state.decompose(id)).collect();
+    assert!(decomposed.into_iter().eq(ids.iter().map(|&id| state.decompose(id))));
+
+    let hashed: std::collections::HashSet<_> = ids.iter().chain(&ids).map(|&id| state.decompose(id)).collect();
+    assert_eq!(hashed.len(), ids.len());
+    let other_worker = crate::SnowflakeDecomposed { worker_id: 4, ..state.decompose(ids[0]) };
+    // The worker ID takes precedence over the sequence.
+    assert!(state.decompose(ids[1]) < other_worker && other_worker < state.decompose(ids[2]));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_decomposed_serde_round_trip() {
+    let _serial = serial();
     let generator = STSG::new(0, 1).unwrap();
-    let ids = 1_000_000;
-    let time = std::time::Instant::now();
-    for _ in 0..ids {
-        generator.generate_id();
+    let decomposed = generator.decompose(generator.generate_id());
+    let json = serde_json::to_string(&decomposed).unwrap();
+    assert!(json.contains("\"timestamp\""));
+    assert!(json.contains("\"worker_id\":1"));
+    assert!(json.contains("\"sequence\""));
+    let parsed: crate::SnowflakeDecomposed = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, decomposed);
+}
+
+#[test]
+fn test_snowflake_id_newtype() {
+    let _serial = serial();
+    let generator = MTSG::new(1_000, 1).unwrap();
+    let id1 = generator.generate();
+    let id2 = generator.generate();
+    assert!(id2 > id1);
+    assert_eq!(id1.to_string(), id1.as_i64().to_string());
+    assert_eq!(i64::from(id1), id1.as_i64());
+    assert_eq!(id1.decompose(1_000).unwrap(), generator.decompose(id1.as_i64()));
+}
+
+#[test]
+fn test_base62_round_trip() {
+    use crate::encoding::{decode_base62, encode_base62};
+    let mut values: Vec<i64> = (0..1_000).collect();
+    let mut power: i64 = 1;
+    while let Some(next) = power.checked_mul(62) {
+        values.extend([power - 1, power, power + 1]);
+        power = next;
     }
-    let elapsed = time.elapsed();
-    println!("Generated {} IDs in {:?}", ids, elapsed);
-    println!("({:.2} IDs/ms)", ids as f64 / elapsed.as_millis() as f64);
+    values.extend((0..1_000).map(|i| i * (i64::MAX / 1_000)));
+    values.extend([i64::MAX - 1, i64::MAX]);
+    for id in values {
+        let encoded = encode_base62(id);
+        assert_eq!(decode_base62(&encoded).unwrap(), id, "round trip of {} via {}", id, encoded);
+    }
+    assert_eq!(encode_base62(0), "0");
+    assert_eq!(encode_base62(61), "z");
+    assert_eq!(encode_base62(62), "10");
+}
+
+#[test]
+fn test_base62_invalid_input() {
+    use crate::encoding::{decode_base62, encode_base62};
+    use crate::SnowflakeDecodeError;
+    for input in ["", "00", "0z", "ab-c", "abc ", "é"] {
+        assert!(matches!(decode_base62(input), Err(SnowflakeDecodeError::InvalidBase62)), "{:?}", input);
+    }
+    // Appending a digit to the encoding of i64::MAX overflows.
+    let max = encode_base62(i64::MAX);
+    let mut overflow = max.clone();
+    overflow.push('0');
+    assert!(matches!(decode_base62(&overflow), Err(SnowflakeDecodeError::InvalidBase62)));
+    assert!(matches!(decode_base62(&"z".repeat(11)), Err(SnowflakeDecodeError::InvalidBase62)));
+}
+
+#[test]
+fn test_padded_decimal_sorts_like_ids() {
+    use crate::encoding::{decode_padded_decimal, encode_padded_decimal};
+    use crate::SnowflakeDecodeError;
+    let _serial = serial();
+    let generator = MTSG::with_clock(0, 1, MockClock::new(1_700_000_000_000)).unwrap();
+    let mut ids: Vec<i64> = generator.generate_ids(100);
+    ids.extend([0, 9, 10, 99, 100, 1 << 22, i64::MAX - 1, i64::MAX]);
+    let mut encoded: Vec<String> = ids.iter().map(|&id| encode_padded_decimal(id)).collect();
+    assert!(encoded.iter().all(|s| s.len() == 19));
+    encoded.sort();
+    ids.sort();
+    let decoded: Vec<i64> = encoded.iter().map(|s| decode_padded_decimal(s).unwrap()).collect();
+    assert_eq!(decoded, ids);
+
+    assert_eq!(encode_padded_decimal(42), "0000000000000000042");
+    assert_eq!(encode_padded_decimal(i64::MAX), i64::MAX.to_string());
+    assert_eq!(decode_padded_decimal("42"), Ok(42));
+    for input in ["", "+42", "-0000000000000000042", "00000000000000000 42", "9223372036854775808"] {
+        assert_eq!(decode_padded_decimal(input), Err(SnowflakeDecodeError::InvalidDecimal), "{:?}", input);
+    }
+}
+
+#[test]
+fn test_crockford_round_trip() {
+    use crate::encoding::{decode_crockford, encode_crockford};
+    let mut values: Vec<i64> = (0..1_000).collect();
+    for shift in (5..63).step_by(5) {
+        let power = 1i64 << shift;
+        values.extend([power - 1, power, power + 1]);
+    }
+    values.extend((0..1_000).map(|i| i * (i64::MAX / 1_000)));
+    values.extend([i64::MAX - 1, i64::MAX]);
+    for id in values {
+        let encoded = encode_crockford(id);
+        assert_eq!(decode_crockford(&encoded).unwrap(), id, "round trip of {} via {}", id, encoded);
+        assert_eq!(decode_crockford(&encoded.to_lowercase()).unwrap(), id);
+    }
+    assert_eq!(encode_crockford(0), "0");
+    assert_eq!(encode_crockford(31), "Z");
+    assert_eq!(encode_crockford(32), "10");
+    assert_eq!(encode_crockford(i64::MAX), "7ZZZZZZZZZZZZ");
+}
+
+#[test]
+fn test_crockford_invalid_input_and_substitutions() {
+    use crate::encoding::decode_crockford;
+    use crate::SnowflakeDecodeError;
+    assert_eq!(decode_crockford("IL").unwrap(), decode_crockford("11").unwrap());
+    assert_eq!(decode_crockford("1o").unwrap(), 32);
+    for input in ["", "U", "ab-c", "abc ", "é", "8ZZZZZZZZZZZZ", "ZZZZZZZZZZZZZZ"] {
+        assert!(matches!(decode_crockford(input), Err(SnowflakeDecodeError::InvalidCrockford)), "{:?}", input);
+    }
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_decomposed_datetime() {
+    let _serial = serial();
+    let generator = STSG::new(1_420_070_400_000, 1).unwrap();
+    let datetime = generator.decompose(generator.generate_id()).datetime();
+    let diff = chrono::Utc::now() - datetime;
+    assert!(diff.num_milliseconds().abs() < 1_000);
+
+    let decomposed = crate::SnowflakeDecomposed { timestamp: -1_500, worker_id: 0, sequence: 0 };
+    assert_eq!(decomposed.datetime().timestamp_millis(), -1_500);
+    let decomposed = crate::SnowflakeDecomposed { timestamp: i64::MAX, worker_id: 0, sequence: 0 };
+    assert_eq!(decomposed.datetime(), chrono::DateTime::<chrono::Utc>::MAX_UTC);
 }
 
+#[cfg(feature = "chrono")]
+#[test]
+fn test_epoch_from_ymd() {
+    use crate::epoch_from_ymd;
+    assert_eq!(epoch_from_ymd(2020, 1, 1), Ok(1_577_836_800_000));
+    assert_eq!(epoch_from_ymd(1970, 1, 1), Ok(0));
+    assert_eq!(epoch_from_ymd(2024, 2, 29), Ok(1_709_164_800_000));
+    assert_eq!(epoch_from_ymd(2023, 2, 29), Err(SnowflakeError::InvalidDate));
+    assert_eq!(epoch_from_ymd(2020, 13, 1), Err(SnowflakeError::InvalidDate));
+    assert_eq!(epoch_from_ymd(2020, 1, 0), Err(SnowflakeError::InvalidDate));
+}
+
+#[test]
+fn test_multi_thread_sync_generate_ids() {
+    let _serial = serial();
+    let generator = MTSG::new(0, 1).unwrap();
+    let ids = generator.generate_ids(10_000);
+    assert_eq!(ids.len(), 10_000);
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    assert!(generator.generate_ids(0).is_empty());
+}
+
+#[test]
+fn test_timestamp_overflow() {
+    use crate::raw::RawSnowflake;
+    let _serial = serial();
+    // The last millisecond of the 41 timestamp bits still works.
+    let clock = MockClock::new((1 << 41) - 1);
+    let mut state = DefaultSnowflake::with_clock(0, 1, clock.clone()).unwrap();
+    let last = state.try_generate_id().unwrap();
+    assert_eq!(state.decompose(last).timestamp, (1 << 41) - 1);
+    assert!(last > 0);
+
+    clock.advance(1);
+    assert_eq!(state.try_generate_id(), Err(SnowflakeError::TimestampOverflow));
+    assert_eq!(state.generate_id_with_backoff(), Err(SnowflakeError::TimestampOverflow));
+    assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| state.generate_id())).is_err());
+    // The extra bit of the u64 IDs lasts longer.
+    assert!(state.try_generate_id_u64().is_ok());
+    // Borrowing from the following milliseconds stops at the last one too.
+    assert_eq!(state.try_generate_id_monotonic(), Err(SnowflakeError::TimestampOverflow));
+    assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| state.generate_id_monotonic())).is_err());
+    drop(state);
+
+    let clock = MockClock::new((1 << 41) - 1);
+    let atomic = MTAtG::with_clock(0, 1, clock.clone()).unwrap();
+    let last = atomic.generate_id();
+    assert_eq!(atomic.decompose(last).timestamp, (1 << 41) - 1);
+    clock.advance(1);
+    assert!(std::panic::catch_unwind(|| atomic.generate_id()).is_err());
+    drop(atomic);
+
+    let mut state = SnowflakeState::<39, 14, 10, MockClock>::with_clock(-(1 << 39), 1, MockClock::new(0)).unwrap();
+    assert_eq!(state.try_generate_id(), Err(SnowflakeError::TimestampOverflow));
+
+    // An epoch so far in the past that the time since the epoch overflows an i64.
+    assert_eq!(RawSnowflake::<41, 10, 12>::new(i64::MIN, 1, 1).err(), Some(SnowflakeError::TimestampOverflow));
+    let mut raw = RawSnowflake::<41, 10, 12>::new(i64::MIN, 1, -1).unwrap();
+    assert_eq!(raw.generate_id(1), Err(SnowflakeError::TimestampOverflow));
+}
+
+#[test]
+fn test_checksum_ids() {
+    use crate::raw::RawSnowflake;
+    use crate::verify_checksum;
+    let _serial = serial();
+    let clock = MockClock::new(5_000);
+    let mut state = DefaultSnowflake::with_clock(1_000, 0x2A5, clock.clone()).unwrap();
+    let mut ids = vec![state.generate_id(), state.generate_id_with_checksum(), state.generate_id()];
+    for _ in 0..100 {
+        ids.push(state.generate_id_with_checksum());
+    }
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+
+    for &id in &ids[3..] {
+        assert!(verify_checksum(id));
+        for bit in 0..64 {
+            assert!(!verify_checksum(id ^ (1 << bit)), "flipping bit {} of {} went unnoticed", bit, id);
+        }
+    }
+    // The plain ID took sequence 0, so the checksummed one starts at the next block.
+    assert_eq!(state.decompose(ids[1]).sequence >> 2, 1);
+    assert!(!verify_checksum(-1));
+
+    // Only a quarter of the sequence numbers fit in a millisecond.
+    let mut raw = RawSnowflake::<41, 10, 12>::new(0, 1, 0).unwrap();
+    for _ in 0..1024 {
+        raw.generate_id_with_checksum(7).unwrap();
+    }
+    assert_eq!(raw.generate_id_with_checksum(7), Err(SnowflakeError::SequenceExhausted));
+    assert!(verify_checksum(raw.generate_id_with_checksum(8).unwrap()));
+
+    let mut strided = DefaultSnowflake::with_clock(0, 1, clock).unwrap().sequence_stride(2, 0).unwrap();
+    assert_eq!(strided.try_generate_id_with_checksum(), Err(SnowflakeError::InvalidSequenceStride));
+    assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| strided.generate_id_with_checksum())).is_err());
+}
+
+#[cfg(feature = "test-util")]
+#[test]
+fn test_deterministic_generator() {
+    use crate::test_util::DeterministicGenerator;
+    let mut generator = DeterministicGenerator::new(1_288_834_974_657, 1, 1_700_000_000_000).unwrap();
+    assert_eq!(generator.generate_id(), 1_724_551_110_456_250_368);
+    assert_eq!(generator.generate_id(), 1_724_551_110_456_250_369);
+    assert_eq!(generator.generate_id(), 1_724_551_110_456_250_370);
+
+    // Running out of sequence numbers moves to the next millisecond instead of waiting.
+    let mut last = 0;
+    for _ in 3..4097 {
+        last = generator.generate_id();
+    }
+    assert_eq!(generator.decompose(last).timestamp, 1_700_000_000_001);
+    assert_eq!(generator.decompose(last).sequence, 0);
+    generator.advance(10);
+    assert_eq!(generator.now_millis(), 1_700_000_000_011);
+    let id = generator.generate_id();
+    assert_eq!(generator.decompose(id).timestamp, 1_700_000_000_011);
+}
+
+#[test]
+fn test_u64_ids_near_max() {
+    use crate::raw::RawSnowflake;
+    let _serial = serial();
+    let epoch = 1_000;
+    let last_ms = epoch + (1 << 42) - 1;
+    let mut raw = RawSnowflake::<41, 10, 12>::new(epoch, 0x3FF, last_ms).unwrap();
+    raw.sequence = 0xFFE;
+    let id = raw.generate_id_u64(last_ms).unwrap();
+    assert_eq!(id, u64::MAX - 1);
+    assert_eq!(raw.generate_id_u64(last_ms).unwrap(), u64::MAX);
+    assert!(matches!(raw.generate_id_u64(last_ms), Err(SnowflakeError::SequenceExhausted)));
+    let decomposed = crate::decompose_u64(u64::MAX, epoch);
+    assert_eq!(decomposed, crate::SnowflakeDecomposed { timestamp: last_ms, worker_id: 0x3FF, sequence: 0xFFF });
+    assert_eq!(raw.decompose_u64(id).sequence, 0xFFE);
+
+    // Past 2^41 ms the top bit is used, which the i64 IDs cannot represent.
+    let past_i64_range = epoch + (1 << 41);
+    let mut raw = RawSnowflake::<41, 10, 12>::new(epoch, 5, past_i64_range).unwrap();
+    let id = raw.generate_id_u64(past_i64_range).unwrap();
+    assert!(id > i64::MAX as u64);
+    assert_eq!(crate::decompose_u64(id, epoch), crate::SnowflakeDecomposed { timestamp: past_i64_range, worker_id: 5, sequence: 0 });
+
+    // Within the i64 range, both agree.
+    let clock = MockClock::new(10_000);
+    let generator = MTSG::with_clock(epoch, 7, clock.clone()).unwrap();
+    let id = generator.generate_id_u64();
+    assert_eq!(crate::decompose_u64(id, epoch), generator.decompose(id as i64));
+    assert_eq!(generator.generate_id() as u64, id + 1);
+    drop(generator);
+    let single = STSG::with_clock(epoch, 7, clock).unwrap();
+    assert_eq!(crate::decompose_u64(single.generate_id_u64(), epoch).timestamp, 10_000);
+}
+
+#[test]
+fn test_generate_into() {
+    let _serial = serial();
+    let mut buf = [0i64; 1000];
+    STSG::quick().generate_into(&mut buf);
+    assert!(buf.windows(2).all(|w| w[0] < w[1]));
+
+    let generator = MTSG::new(0, 2).unwrap();
+    generator.generate_into(&mut buf);
+    assert!(buf.windows(2).all(|w| w[0] < w[1]));
+    assert!(buf.iter().all(|&id| generator.decompose(id).worker_id == 2));
+
+    // Spans at least two sequence rollovers.
+    let mut large = vec![0i64; 10_000];
+    generator.generate_into(&mut large);
+    assert!(large.windows(2).all(|w| w[0] < w[1]));
+    assert!(buf[999] < large[0]);
+    generator.generate_into(&mut []);
+}
+
+#[cfg(feature = "tokio")]
 #[tokio::test]
-async fn bench_multi_thread_async_snowflake_id_generation() {
-    use std::thread;
+async fn test_multi_thread_async_generate_ids_across_rollover() {
+    let _serial = serial_async().await;
     let generator = MTAG::new(0, 1).unwrap();
-    let ids_per_thread = 100_000;
-    let threads = 10;
-    let time = std::time::Instant::now();
+    let other = {
+        let gen_clone = generator.clone();
+        tokio::spawn(async move { gen_clone.generate_ids(5_000).await })
+    };
+    // More than 4096 IDs forces at least one rollover within the batch.
+    let ids = generator.generate_ids(10_000).await;
+    let other_ids = other.await.unwrap();
+    assert_eq!(ids.len(), 10_000);
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    assert!(other_ids.windows(2).all(|w| w[0] < w[1]));
+
+    let mut all: Vec<i64> = ids.into_iter().chain(other_ids).collect();
+    all.sort_unstable();
+    all.dedup();
+    assert_eq!(all.len(), 15_000);
+}
+
+#[test]
+fn test_multi_thread_atomic_snowflake_id_generation() {
+    use std::thread;
+    let _serial = serial();
+    let generator = MTAtG::new(0, 1).unwrap();
     let mut handles = vec![];
-    for _ in 0..threads {
+    for _ in 0..4 {
         let gen_clone = generator.clone();
-        let handle = thread::spawn(async move || {
-            for _ in 0..ids_per_thread {
-                gen_clone.generate_id().await;
-            }
-        });
-        handles.push(handle);
+        handles.push(thread::spawn(move || (0..10_000).map(|_| gen_clone.generate_id()).collect::<Vec<_>>()));
     }
+    let mut all = vec![];
     for handle in handles {
-        handle.join().unwrap().await;
+        let ids = handle.join().unwrap();
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+        all.extend(ids);
     }
-    let elapsed = time.elapsed();
-    println!("Generated {} IDs in {:?}", ids_per_thread * threads, elapsed);
-    println!("({:.2} IDs/ms)", (ids_per_thread * threads) as f64 / elapsed.as_millis() as f64);
+    all.sort_unstable();
+    all.dedup();
+    assert_eq!(all.len(), 40_000);
+    assert_eq!(generator.decompose(all[0]).worker_id, 1);
 }
 
 #[test]
-fn bench_multi_thread_sync_snowflake_id_generation() {
-    use std::thread;
-    let generator = MTSG::new(0, 1).unwrap();
-    let ids_per_thread = 100_000;
-    let threads = 10;
-    let time = std::time::Instant::now();
-    let mut handles = vec![];
-    for _ in 0..threads {
+fn test_atomic_generator_matches_mutex_layout() {
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let atomic = MTAtG::with_clock(1_000, 5, clock.clone()).unwrap();
+    let atomic_ids: Vec<i64> = (0..100).map(|_| atomic.generate_id()).collect();
+    // dedup-check rejects two live generators on worker 5.
+    drop(atomic);
+    let mutex = MTSG::with_clock(1_000, 5, clock.clone()).unwrap();
+    let mutex_ids: Vec<i64> = (0..100).map(|_| mutex.generate_id()).collect();
+    assert_eq!(atomic_ids, mutex_ids);
+}
+
+#[test]
+fn test_atomic_generator_rollover_and_clock_backwards() {
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let generator = MTAtG::with_clock(0, 1, clock.clone()).unwrap();
+    let first = generator.generate_id();
+    clock.set(9_000);
+    // The clock moved backwards: keep issuing IDs in the last millisecond.
+    let ids: Vec<i64> = (0..0xFFF).map(|_| generator.generate_id()).collect();
+    assert!(ids[0] > first);
+    assert!(ids.iter().all(|&id| generator.decompose(id).timestamp == 10_000));
+
+    // The sequence is now exhausted, so the next call spins until the clock advances.
+    let handle = {
         let gen_clone = generator.clone();
-        let handle = thread::spawn(move || {
-            for _ in 0..ids_per_thread {
-                gen_clone.generate_id();
-            }
-        });
-        handles.push(handle);
+        std::thread::spawn(move || gen_clone.generate_id())
+    };
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    clock.set(10_001);
+    let next = handle.join().unwrap();
+    let decomposed = generator.decompose(next);
+    assert_eq!((decomposed.timestamp, decomposed.sequence), (10_001, 0));
+}
+
+#[test]
+fn test_epoch_in_future_is_an_error() {
+    let _serial = serial();
+    assert!(matches!(STSG::new(i64::MAX, 1), Err(SnowflakeError::EpochInFuture)));
+    assert!(matches!(MTSG::new(i64::MAX, 1), Err(SnowflakeError::EpochInFuture)));
+    #[cfg(feature = "tokio")]
+    assert!(matches!(MTAG::new(i64::MAX, 1), Err(SnowflakeError::EpochInFuture)));
+    assert!(matches!(MTAtG::new(i64::MAX, 1), Err(SnowflakeError::EpochInFuture)));
+    assert!(matches!(DefaultSnowflake::new(i64::MAX, 1), Err(SnowflakeError::EpochInFuture)));
+}
+
+#[test]
+fn test_peek_id_matches_next_generated_id() {
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let single = STSG::with_clock(0, 1, clock.clone()).unwrap();
+    let multi = MTSG::with_clock(0, 2, clock.clone()).unwrap();
+    let atomic = MTAtG::with_clock(0, 3, clock.clone()).unwrap();
+    for _ in 0..=0xFFF {
+        let peeked = single.peek_id();
+        assert_eq!(single.peek_id(), peeked);
+        assert_eq!(single.generate_id(), peeked);
+        let peeked = multi.peek_id();
+        assert_eq!(multi.generate_id(), peeked);
+        let peeked = atomic.peek_id();
+        assert_eq!(atomic.generate_id(), peeked);
     }
-    for handle in handles {
-        handle.join().unwrap();
+    // The sequence is exhausted, so the next ID belongs to the next millisecond.
+    for peeked in [single.peek_id(), multi.peek_id(), atomic.peek_id()] {
+        let decomposed = single.decompose(peeked);
+        assert_eq!((decomposed.timestamp, decomposed.sequence), (10_001, 0));
     }
-    let elapsed = time.elapsed();
-    println!("Generated {} IDs in {:?}", ids_per_thread * threads, elapsed);
-    println!("({:.2} IDs/ms)", (ids_per_thread * threads) as f64 / elapsed.as_millis() as f64);
-}
\ No newline at end of file
+    clock.advance(1);
+    assert_eq!(single.peek_id(), single.generate_id());
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_async_peek_id_matches_next_generated_id() {
+    let _serial = serial_async().await;
+    let clock = MockClock::new(10_000);
+    let generator = MTAG::with_clock(0, 1, clock.clone()).unwrap();
+    for _ in 0..10 {
+        let peeked = generator.peek_id().await;
+        assert_eq!(generator.generate_id().await, peeked);
+    }
+}
+
+#[test]
+fn test_worker_id_from_hostname() {
+    use crate::worker_id::{worker_id_from_bytes, worker_id_from_hostname};
+    let _serial = serial();
+    let worker_id = worker_id_from_hostname().unwrap();
+    assert!(worker_id <= 0x3FF);
+    assert_eq!(worker_id_from_hostname().unwrap(), worker_id);
+    assert!(STSG::new(0, worker_id).is_ok());
+    for i in 0..10_000u32 {
+        assert!(worker_id_from_bytes(&i.to_le_bytes()) <= 0x3FF);
+    }
+    assert_eq!(worker_id_from_bytes(b"host-a"), worker_id_from_bytes(b"host-a"));
+}
+
+#[test]
+fn test_worker_id_from_env() {
+    use crate::worker_id::worker_id_from_env;
+    static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    let _guard = ENV_LOCK.lock().unwrap();
+    let var = "SNOWFLAKE_ID_TEST_WORKER_ID";
+    let set = |value: &str| unsafe { std::env::set_var(var, value) };
+
+    set(" 42\n");
+    assert_eq!(worker_id_from_env(var).unwrap(), 42);
+    set("1023");
+    assert_eq!(worker_id_from_env(var).unwrap(), 1023);
+    set("1024");
+    assert!(matches!(worker_id_from_env(var), Err(SnowflakeError::WorkerIdOutOfRange)));
+    set("worker-1");
+    assert!(matches!(worker_id_from_env(var), Err(SnowflakeError::EnvVarInvalid)));
+    set("");
+    assert!(matches!(worker_id_from_env(var), Err(SnowflakeError::EnvVarInvalid)));
+    unsafe { std::env::remove_var(var) };
+    assert!(matches!(worker_id_from_env(var), Err(SnowflakeError::EnvVarMissing)));
+}
+
+#[test]
+fn test_worker_id_from_pod_name() {
+    use crate::worker_id::worker_id_from_pod_name;
+    assert_eq!(worker_id_from_pod_name("web-5").unwrap(), 5);
+    assert_eq!(worker_id_from_pod_name("id-service-web-1023").unwrap(), 1023);
+    for name in ["web", "web-", "web-5a", "web-+5", "5"] {
+        assert!(matches!(worker_id_from_pod_name(name), Err(SnowflakeError::MissingPodOrdinal)));
+    }
+    assert!(matches!(worker_id_from_pod_name("web-2000"), Err(SnowflakeError::WorkerIdOutOfRange)));
+    assert!(matches!(worker_id_from_pod_name("web-99999999999999999999"), Err(SnowflakeError::WorkerIdOutOfRange)));
+}
+
+#[cfg(feature = "machine-id")]
+#[test]
+fn test_worker_id_from_mac() {
+    match crate::worker_id::worker_id_from_mac() {
+        Ok(worker_id) => assert!(worker_id <= 0x3FF),
+        Err(e) => assert!(matches!(e, SnowflakeError::MachineIdUnavailable)),
+    }
+}
+
+#[test]
+fn test_layout_constants() {
+    use crate::{MAX_SEQUENCE, MAX_WORKER_ID, SEQUENCE_BITS, TIMESTAMP_BITS, WORKER_BITS};
+    let _serial = serial();
+    assert_eq!(MAX_WORKER_ID, 1023);
+    assert_eq!(MAX_SEQUENCE, 4095);
+    assert_eq!(TIMESTAMP_BITS + WORKER_BITS + SEQUENCE_BITS, 63);
+
+    let clock = MockClock::new(10_000);
+    let mut state = DefaultSnowflake::with_clock(0, MAX_WORKER_ID, clock.clone()).unwrap();
+    let id = state.generate_id();
+    assert_eq!(state.decompose(id).worker_id, MAX_WORKER_ID);
+    assert!(matches!(
+        DefaultSnowflake::with_clock(0, MAX_WORKER_ID + 1, clock.clone()),
+        Err(SnowflakeError::WorkerIdOutOfRange)
+    ));
+    drop(state);
+    let atomic = MTAtG::with_clock(0, MAX_WORKER_ID, clock).unwrap();
+    for _ in 0..=MAX_SEQUENCE {
+        let _ = atomic.generate_id();
+    }
+    assert_eq!(atomic.decompose(atomic.peek_id()).sequence, 0);
+}
+
+#[test]
+fn test_decompose_entry_points_share_one_type() {
+    let _serial = serial();
+    let mut state = DefaultSnowflake::new(1_000, 7).unwrap();
+    let id = state.generate_id();
+    let from_state: crate::SnowflakeDecomposed = state.decompose(id);
+    let from_fn: crate::SnowflakeDecomposed = crate::decompose_snowflake(id, 1_000).unwrap();
+    assert_eq!(from_state, from_fn);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_epoch_getters() {
+    let _serial = serial_async().await;
+    let clock = MockClock::new(10_000);
+    let state = DefaultSnowflake::with_clock(1_234, 7, clock.clone()).unwrap();
+    assert_eq!(state.epoch(), 1_234);
+    let single = STSG::with_clock(1_234, 8, clock.clone()).unwrap();
+    assert_eq!(single.epoch(), 1_234);
+    let multi = MTSG::with_clock(1_234, 9, clock.clone()).unwrap();
+    assert_eq!(multi.epoch(), 1_234);
+    let asynchronous = MTAG::with_clock(1_234, 10, clock.clone()).unwrap();
+    assert_eq!(asynchronous.epoch().await, 1_234);
+    let atomic = MTAtG::with_clock(1_234, 11, clock).unwrap();
+    assert_eq!(atomic.epoch(), 1_234);
+
+    let id = multi.generate_id();
+    assert_eq!(crate::decompose_snowflake(id, multi.epoch()).unwrap(), multi.decompose(id));
+}
+
+#[test]
+fn test_is_from_self() {
+    let _serial = serial();
+    let mut state = DefaultSnowflake::new(0, 7).unwrap();
+    let mut other = DefaultSnowflake::new(0, 8).unwrap();
+    let own = state.generate_id();
+    let foreign = other.generate_id();
+    assert!(state.is_from_self(own));
+    assert!(!state.is_from_self(foreign));
+    assert!(!state.is_from_self(own | i64::MIN));
+    assert!(state.assert_from_self(own).is_ok());
+    assert!(matches!(
+        state.assert_from_self(foreign),
+        Err(SnowflakeError::WorkerIdMismatch { expected: 7, found: 8 })
+    ));
+    assert!(matches!(state.assert_from_self(-1), Err(SnowflakeError::NegativeId)));
+}
+
+#[cfg(feature = "parking_lot")]
+#[test]
+fn test_parking_lot_generator_survives_panic_while_locked() {
+    use crate::multi_thread::parking_lot_generator::SnowflakeGenerator as MTPLG;
+    use std::sync::atomic::AtomicBool;
+    let _serial = serial();
+
+    #[derive(Clone)]
+    struct PanickingClock {
+        clock: MockClock,
+        panic: Arc<AtomicBool>,
+    }
+
+    impl ClockSource for PanickingClock {
+        fn now_millis(&self) -> i64 {
+            if self.panic.load(Ordering::SeqCst) {
+                panic!("clock failure");
+            }
+            self.clock.now_millis()
+        }
+    }
+
+    let clock = PanickingClock { clock: MockClock::new(10_000), panic: Arc::new(AtomicBool::new(false)) };
+    let generator = MTPLG::with_clock(0, 1, clock.clone()).unwrap();
+    let id1 = generator.generate_id();
+
+    // Panic inside generate_id, while the lock is held.
+    clock.panic.store(true, Ordering::SeqCst);
+    let gen_clone = generator.clone();
+    assert!(std::thread::spawn(move || gen_clone.generate_id()).join().is_err());
+    clock.panic.store(false, Ordering::SeqCst);
+
+    let id2 = generator.generate_id();
+    assert!(id2 > id1);
+}
+
+#[test]
+fn test_thread_local_generator() {
+    use crate::thread_local_generator::{configure, generate_id};
+    let _serial = serial();
+    configure(1_000, 100).unwrap();
+    assert!(matches!(configure(1_000, 100), Err(SnowflakeError::AlreadyConfigured)));
+
+    let handle1 = std::thread::spawn(|| (generate_id().unwrap(), generate_id().unwrap()));
+    let handle2 = std::thread::spawn(|| generate_id().unwrap());
+    let (id1, id2) = handle1.join().unwrap();
+    let id3 = handle2.join().unwrap();
+    assert!(id2 > id1);
+
+    let worker1 = crate::decompose_snowflake(id1, 1_000).unwrap().worker_id;
+    let worker2 = crate::decompose_snowflake(id2, 1_000).unwrap().worker_id;
+    let worker3 = crate::decompose_snowflake(id3, 1_000).unwrap().worker_id;
+    assert_eq!(worker1, worker2);
+    assert_ne!(worker1, worker3);
+    assert!(worker1 >= 100 && worker3 >= 100);
+}
+
+#[cfg(feature = "global")]
+#[test]
+fn test_global_generator() {
+    use crate::global::{generate_id, init, try_generate_id};
+    let _serial = serial();
+    assert!(matches!(try_generate_id(), Err(SnowflakeError::NotInitialized)));
+    assert!(matches!(crate::global::generator(), Err(SnowflakeError::NotInitialized)));
+    assert!(std::panic::catch_unwind(generate_id).is_err());
+    assert!(matches!(init(1_000, 1_024), Err(SnowflakeError::WorkerIdOutOfRange)));
+
+    // The global generator is never dropped, so its worker ID is not used by any other test.
+    init(1_000, 870).unwrap();
+    assert!(matches!(init(1_000, 78), Err(SnowflakeError::AlreadyConfigured)));
+    let id1 = generate_id();
+    let id2 = std::thread::spawn(|| try_generate_id().unwrap()).join().unwrap();
+    assert!(id2 > id1);
+    assert_eq!(crate::decompose_snowflake(id2, 1_000).unwrap().worker_id, 870);
+}
+
+#[test]
+fn test_decomposed_system_time() {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    let _serial = serial();
+    let generator = STSG::new(1_420_070_400_000, 1).unwrap();
+    let system_time = generator.decompose(generator.generate_id()).system_time();
+    let now = SystemTime::now();
+    let diff = now.duration_since(system_time).unwrap_or_else(|e| e.duration());
+    assert!(diff < Duration::from_millis(100));
+
+    let decomposed = crate::SnowflakeDecomposed { timestamp: -1_500, worker_id: 0, sequence: 0 };
+    assert_eq!(decomposed.system_time(), UNIX_EPOCH - Duration::from_millis(1_500));
+}
+
+#[test]
+fn test_sequence_never_wraps_within_a_millisecond() {
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let mut state = DefaultSnowflake::with_clock(0, 1, clock).unwrap();
+    let mut ids = vec![];
+    let mut exhausted = 0;
+    for _ in 0..5_000 {
+        match state.try_generate_id() {
+            Ok(id) => ids.push(id),
+            Err(SnowflakeError::SequenceExhausted) => exhausted += 1,
+            Err(e) => panic!("unexpected error: {}", e),
+        }
+    }
+    assert_eq!(ids.len(), 4096);
+    assert_eq!(exhausted, 5_000 - 4096);
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(state.decompose(*ids.last().unwrap()).sequence, 0xFFF);
+}
+
+#[tokio::test]
+async fn test_builder() {
+    use crate::SnowflakeBuilder;
+    let _serial = serial_async().await;
+    let clock = MockClock::new(10_000);
+    let single = SnowflakeBuilder::new().epoch(1_000).worker_id(5).clock(clock.clone()).build_single_thread().unwrap();
+    let decomposed = single.decompose(single.generate_id());
+    assert_eq!((decomposed.timestamp, decomposed.worker_id), (10_000, 5));
+
+    let multi = SnowflakeBuilder::new().datacenter_id(2).worker_id(3).build_sync().unwrap();
+    let decomposed = multi.decompose(multi.generate_id());
+    assert_eq!((decomposed.datacenter_id(), decomposed.datacenter_worker_id()), (2, 3));
+
+    #[cfg(feature = "tokio")]
+    {
+        let asynchronous = SnowflakeBuilder::default().worker_id(9).build_async().unwrap();
+        assert_eq!(asynchronous.decompose(asynchronous.generate_id().await).await.worker_id, 9);
+        assert!(matches!(SnowflakeBuilder::new().epoch(i64::MAX).build_async(), Err(SnowflakeError::EpochInFuture)));
+    }
+
+    assert!(matches!(SnowflakeBuilder::new().worker_id(1024).build_sync(), Err(SnowflakeError::WorkerIdOutOfRange)));
+    assert!(matches!(
+        SnowflakeBuilder::new().datacenter_id(1).worker_id(32).build_sync(),
+        Err(SnowflakeError::WorkerIdOutOfRange)
+    ));
+    assert!(matches!(
+        SnowflakeBuilder::new().datacenter_id(32).build_single_thread(),
+        Err(SnowflakeError::DatacenterIdOutOfRange)
+    ));
+}
+
+#[test]
+fn test_decompose_const() {
+    // Timestamp 1000, worker 1, sequence 2.
+    const DECOMPOSED: crate::SnowflakeDecomposed = crate::decompose_const((1_000 << 22) | (1 << 12) | 2, 0);
+    const _: () = assert!(DECOMPOSED.worker_id == 1);
+    assert_eq!(DECOMPOSED, crate::SnowflakeDecomposed { timestamp: 1_000, worker_id: 1, sequence: 2 });
+    assert_eq!(DECOMPOSED, crate::decompose_snowflake(4_194_308_098, 0).unwrap());
+}
+
+#[test]
+fn test_generate_id_at_backfill() {
+    let _serial = serial();
+    let clock = MockClock::new(100_000);
+    let single = STSG::with_clock(1_000, 1, clock.clone()).unwrap();
+    let ids: Vec<i64> = (0..3).map(|_| single.generate_id_at(50_000).unwrap()).collect();
+    assert!(ids[0] < ids[1] && ids[1] < ids[2]);
+    for (sequence, &id) in ids.iter().enumerate() {
+        let decomposed = single.decompose(id);
+        assert_eq!((decomposed.timestamp, decomposed.sequence), (50_000, sequence as u16));
+    }
+    assert!(matches!(single.generate_id_at(999), Err(SnowflakeError::TimestampBeforeEpoch)));
+    assert!(matches!(single.generate_id_at(100_000), Err(SnowflakeError::TimestampNotInPast)));
+    drop(single);
+
+    let multi = MTSG::with_clock(1_000, 1, clock.clone()).unwrap();
+    let id = multi.generate_id_at(50_000).unwrap();
+    assert_eq!(id, ids[0]);
+    assert_eq!(multi.decompose(multi.generate_id_at(50_000).unwrap()).sequence, 1);
+}
+
+#[test]
+fn test_snowflake_id_from_str() {
+    use crate::SnowflakeDecodeError;
+    use crate::id::SnowflakeId;
+    let _serial = serial();
+    let id = STSG::new(0, 1).unwrap().generate();
+    assert_eq!(id.to_string().parse::<SnowflakeId>().unwrap(), id);
+    assert_eq!("123456".parse::<SnowflakeId>().unwrap(), SnowflakeId::new(123_456));
+    assert!(matches!("-1".parse::<SnowflakeId>(), Err(SnowflakeDecodeError::NegativeId)));
+    assert!(matches!("abc".parse::<SnowflakeId>(), Err(SnowflakeDecodeError::InvalidDecimal)));
+    assert!(matches!("".parse::<SnowflakeId>(), Err(SnowflakeDecodeError::InvalidDecimal)));
+    assert!(matches!("9223372036854775808".parse::<SnowflakeId>(), Err(SnowflakeDecodeError::InvalidDecimal)));
+
+    assert_eq!(SnowflakeId::try_from(42).unwrap().as_i64(), 42);
+    assert!(matches!(SnowflakeId::try_from(i64::MIN), Err(SnowflakeError::NegativeId)));
+}
+
+#[test]
+fn test_extended_generator_round_trip() {
+    use crate::extended::{decompose_extended, ExtendedDecomposed, SnowflakeGenerator as ExtG};
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let generator = ExtG::with_clock(1_000, 0xBEEF, clock.clone()).unwrap();
+    // Far more IDs than fit in a millisecond of the 64-bit layout.
+    let ids: Vec<u128> = (0..10_000).map(|_| generator.generate_id()).collect();
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(
+        generator.decompose(ids[9_999]),
+        ExtendedDecomposed { timestamp: 10_000, worker_id: 0xBEEF, sequence: 9_999 }
+    );
+
+    clock.advance(1);
+    let id = generator.generate();
+    assert_eq!(id.decompose(1_000), ExtendedDecomposed { timestamp: 10_001, worker_id: 0xBEEF, sequence: 0 });
+    assert_eq!(decompose_extended(u128::from(id), 1_000), id.decompose(1_000));
+}
+
+#[cfg(feature = "uuid")]
+#[test]
+fn test_extended_id_uuid_round_trip() {
+    use crate::extended::{ExtendedId, SnowflakeGenerator as ExtG};
+    let _serial = serial();
+    let generator = ExtG::new(0, 1).unwrap();
+    let id1 = generator.generate();
+    let id2 = generator.generate();
+    let (uuid1, uuid2) = (id1.to_uuid(), id2.to_uuid());
+    assert_eq!(uuid1.as_u128(), id1.as_u128());
+    assert_eq!(ExtendedId::from_uuid(uuid1), id1);
+    assert!(uuid1 < uuid2);
+    assert!(uuid1.as_bytes() < uuid2.as_bytes());
+}
+
+#[cfg(feature = "sqlx")]
+#[tokio::test]
+async fn test_snowflake_id_sqlx_round_trip() {
+    use crate::id::SnowflakeId;
+    let _serial = serial_async().await;
+    let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+    let id = STSG::new(0, 1).unwrap().generate();
+    let back: SnowflakeId = sqlx::query_scalar("SELECT ?")
+        .bind(id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(back, id);
+    let negative = sqlx::query_scalar::<_, SnowflakeId>("SELECT -1")
+        .fetch_one(&pool)
+        .await;
+    assert!(negative.is_err());
+}
+
+#[test]
+fn test_compare_by_time() {
+    use crate::{compare_by_time, compare_by_time_then_sequence};
+    use std::cmp::Ordering;
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let worker_a = STSG::with_clock(0, 900, clock.clone()).unwrap();
+    let worker_b = STSG::with_clock(0, 1, clock.clone()).unwrap();
+    let a0 = worker_a.generate_id();
+    let b0 = worker_b.generate_id();
+    let b1 = worker_b.generate_id();
+    // Raw comparison orders same-millisecond IDs by worker.
+    assert!(a0 > b1);
+    assert_eq!(compare_by_time(a0, b1), Ordering::Equal);
+    assert_eq!(compare_by_time_then_sequence(a0, b0), Ordering::Equal);
+    assert_eq!(compare_by_time_then_sequence(a0, b1), Ordering::Less);
+
+    clock.advance(1);
+    let b2 = worker_b.generate_id();
+    assert_eq!(compare_by_time(a0, b2), Ordering::Less);
+    let mut ids = [b2, a0, b1, b0];
+    ids.sort_by(|&x, &y| compare_by_time_then_sequence(x, y));
+    assert_eq!(ids[3], b2);
+    assert_eq!(ids[2], b1);
+}
+
+#[test]
+fn test_compare_across_epochs() {
+    use crate::compare_across_epochs;
+    use std::cmp::Ordering;
+    let _serial = serial();
+    const EPOCH_2020: i64 = 1_577_836_800_000;
+    let clock = MockClock::new(1_700_000_000_000);
+    let legacy = STSG::with_clock(0, 1, clock.clone()).unwrap();
+    let recent = STSG::with_clock(EPOCH_2020, 2, clock.clone()).unwrap();
+    let a = recent.generate_id();
+    clock.advance(5);
+    let b = legacy.generate_id();
+    let c = recent.generate_id();
+    // The raw values order the IDs of the later epoch first.
+    assert!(a < b && c < b);
+    assert_eq!(compare_across_epochs(a, EPOCH_2020, b, 0), Ordering::Less);
+    assert_eq!(compare_across_epochs(b, 0, c, EPOCH_2020), Ordering::Equal);
+    let d = recent.generate_id();
+    assert_eq!(compare_across_epochs(b, 0, d, EPOCH_2020), Ordering::Less);
+
+    let mut merged = [(d, EPOCH_2020), (b, 0), (a, EPOCH_2020)];
+    merged.sort_by(|&(x, epoch_x), &(y, epoch_y)| compare_across_epochs(x, epoch_x, y, epoch_y));
+    assert_eq!(merged, [(a, EPOCH_2020), (b, 0), (d, EPOCH_2020)]);
+    assert_eq!(compare_across_epochs(i64::MAX, i64::MAX, 0, 0), Ordering::Greater);
+}
+
+#[test]
+fn test_new_strict_reserved_worker_id() {
+    use crate::common::DefaultSnowflake;
+    let _serial = serial();
+    assert!(DefaultSnowflake::new_strict(0, 1022).is_ok());
+    assert!(matches!(DefaultSnowflake::new_strict(0, 1023), Err(SnowflakeError::ReservedWorkerId)));
+    assert!(DefaultSnowflake::new(0, 1023).is_ok());
+    assert!(matches!(DefaultSnowflake::new_strict(0, 1024), Err(SnowflakeError::WorkerIdOutOfRange)));
+    assert!(matches!(DefaultSnowflake::new(0, 1024), Err(SnowflakeError::WorkerIdOutOfRange)));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_channel_generator_unique_and_increasing() {
+    use crate::multi_thread::async_generator::ChannelGenerator;
+    let _serial = serial_async().await;
+    let clock = MockClock::new(10_000);
+    let generator = ChannelGenerator::with_clock(0, 7, clock.clone()).unwrap();
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let gen_clone = generator.clone();
+            tokio::spawn(async move {
+                let mut ids = Vec::new();
+                for _ in 0..100 {
+                    ids.push(gen_clone.generate_id().await.unwrap());
+                }
+                ids
+            })
+        })
+        .collect();
+    let mut all = Vec::new();
+    for handle in handles {
+        let ids = handle.await.unwrap();
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+        all.extend(ids);
+    }
+    let len = all.len();
+    all.sort_unstable();
+    all.dedup();
+    assert_eq!(all.len(), len);
+    let decomposed = generator.generate().await.unwrap().decompose(0).unwrap();
+    assert_eq!(decomposed.worker_id, 7);
+    assert_eq!(decomposed.timestamp, 10_000);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_channel_generator_shutdown() {
+    use crate::multi_thread::async_generator::ChannelGenerator;
+    let _serial = serial_async().await;
+    let generator = ChannelGenerator::with_clock(0, 7, MockClock::new(10_000)).unwrap();
+    let clone = generator.clone();
+    assert!(clone.generate_id().await.is_ok());
+    generator.shutdown().await;
+
+    let result = tokio::time::timeout(std::time::Duration::from_secs(5), clone.generate_id()).await;
+    assert_eq!(result.expect("generate_id hung after shutdown"), Err(SnowflakeError::GeneratorClosed));
+    assert_eq!(clone.generate_id_string().await, Err(SnowflakeError::GeneratorClosed));
+    // Shutting down again returns at once.
+    clone.shutdown().await;
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_channel_generator_reports_timestamp_overflow() {
+    use crate::multi_thread::async_generator::ChannelGenerator;
+    let _serial = serial_async().await;
+    let clock = MockClock::new((1 << 41) - 1);
+    let generator = ChannelGenerator::with_clock(0, 317, clock.clone()).unwrap();
+    assert!(generator.generate_id().await.is_ok());
+    clock.advance(1);
+    let result = tokio::time::timeout(std::time::Duration::from_secs(5), generator.generate_id()).await;
+    assert_eq!(result.expect("generate_id hung after the epoch ran out"), Err(SnowflakeError::TimestampOverflow));
+    // The owner task keeps serving requests.
+    assert_eq!(generator.try_generate_id().await, Err(SnowflakeError::TimestampOverflow));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_async_generator_stream() {
+    use futures_util::StreamExt;
+    let _serial = serial_async().await;
+    let generator = MTAG::with_clock(0, 4, MockClock::new(10_000)).unwrap();
+    let mut ids: Vec<i64> = generator.stream().take(100).collect().await;
+    assert_eq!(ids.len(), 100);
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    ids.dedup();
+    assert_eq!(ids.len(), 100);
+    // The stream shares the state with the generator.
+    assert!(generator.generate_id().await > ids[99]);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_async_generators_fallible_paths() {
+    use crate::multi_thread::async_generator::ChannelGenerator;
+    use crate::BackoffStrategy;
+    let _serial = serial_async().await;
+    let clock = MockClock::new(10_000);
+    let generator = MTAG::with_clock(0, 1, clock.clone()).unwrap();
+    generator.set_backoff_strategy(BackoffStrategy::Error).await;
+    for _ in 0..4_096 {
+        generator.generate_id_with_backoff().await.unwrap();
+    }
+    assert_eq!(generator.generate_id_with_backoff().await, Err(SnowflakeError::SequenceExhausted));
+    assert_eq!(generator.try_generate_id().await, Err(SnowflakeError::SequenceExhausted));
+    clock.advance(1);
+    assert!(generator.generate_id_with_backoff().await.is_ok());
+    // Only state errors: dropping every other clone does not close the generator.
+    let clone = generator.clone();
+    drop(generator);
+    clock.set(9_000);
+    assert_eq!(clone.try_generate_id().await, Err(SnowflakeError::ClockMovedBackwards { by_ms: 1_001 }));
+    clock.set(1 << 41);
+    assert_eq!(clone.try_generate_id().await, Err(SnowflakeError::TimestampOverflow));
+    clock.set(10_001);
+
+    let channel = ChannelGenerator::with_clock(0, 2, clock.clone()).unwrap();
+    for _ in 0..4_096 {
+        channel.try_generate_id().await.unwrap();
+    }
+    assert_eq!(channel.try_generate_id().await, Err(SnowflakeError::SequenceExhausted));
+    clock.advance(1);
+    let id = channel.try_generate_id().await.unwrap();
+    assert_eq!(crate::decompose_snowflake(id, 0).unwrap().timestamp, 10_002);
+    channel.clone().shutdown().await;
+    assert_eq!(channel.try_generate_id().await, Err(SnowflakeError::GeneratorClosed));
+}
+
+#[test]
+fn test_min_max_id_for_timestamp() {
+    use crate::{decompose_snowflake, max_id_for_timestamp, min_id_for_timestamp};
+    let _serial = serial();
+    let epoch = 1_000;
+    let ts = 123_456;
+    let (min, max) = (min_id_for_timestamp(ts, epoch), max_id_for_timestamp(ts, epoch));
+    assert!(min < max);
+    let (dmin, dmax) = (decompose_snowflake(min, epoch).unwrap(), decompose_snowflake(max, epoch).unwrap());
+    assert_eq!((dmin.timestamp, dmin.worker_id, dmin.sequence), (ts, 0, 0));
+    assert_eq!((dmax.timestamp, dmax.worker_id, dmax.sequence), (ts, 1023, 4095));
+    assert_eq!(max + 1, min_id_for_timestamp(ts + 1, epoch));
+
+    let clock = MockClock::new(ts);
+    let generator = STSG::with_clock(epoch, 512, clock).unwrap();
+    let id = generator.generate_id();
+    assert!(min <= id && id <= max);
+}
+
+#[test]
+fn test_decompose_snowflake_timestamp_overflow() {
+    use crate::decompose_snowflake;
+    let id = i64::MAX;
+    assert!(matches!(decompose_snowflake(id, i64::MAX), Err(SnowflakeError::TimestampOverflow)));
+    assert!(decompose_snowflake(id, i64::MIN + 1).is_ok());
+    assert!(matches!(decompose_snowflake(-1, 0), Err(SnowflakeError::NegativeId)));
+    assert_eq!(decompose_snowflake(id, 0).unwrap().timestamp, (1 << 41) - 1);
+}
+
+#[test]
+fn test_generate_id_monotonic_tight_loop() {
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let mut state = DefaultSnowflake::with_clock(0, 1, clock.clone()).unwrap();
+    let ids: Vec<i64> = (0..10_000).map(|_| state.generate_id_monotonic()).collect();
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    // 10,000 IDs need three milliseconds of 4096 IDs each.
+    assert_eq!(state.decompose(*ids.last().unwrap()).timestamp, 10_002);
+    clock.set(10_005);
+    let id = state.generate_id_monotonic();
+    assert_eq!(state.decompose(id).timestamp, 10_005);
+}
+
+#[test]
+fn test_with_sequence_start() {
+    let _serial = serial();
+    assert!(matches!(DefaultSnowflake::with_sequence_start(0, 1, 0x1000), Err(SnowflakeError::SequenceOutOfRange)));
+    let mut state = DefaultSnowflake::with_sequence_start(0, 1, 2_000).unwrap();
+    assert_eq!(state.current_sequence(), 2_000);
+    let start = state.time_since_epoch;
+    let id = state.generate_id();
+    let decomposed = state.decompose(id);
+    if decomposed.timestamp == start {
+        assert_eq!(decomposed.sequence, 2_000);
+        assert_eq!(state.current_sequence(), 2_001);
+    } else {
+        // The clock ticked before the first ID, so the sequence restarted.
+        assert_eq!(decomposed.sequence, 0);
+    }
+    assert!(DefaultSnowflake::with_sequence_start(0, 2, 0xFFF).is_ok());
+}
+
+#[test]
+fn test_multi_thread_decompose_while_generating() {
+    use std::thread;
+    let _serial = serial();
+    let generator = MTSG::new(1_000, 5).unwrap();
+    let generated: Vec<i64> = generator.generate_ids(1_000);
+    let producer = {
+        let gen_clone = generator.clone();
+        thread::spawn(move || gen_clone.generate_ids(100_000))
+    };
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let gen_clone = generator.clone();
+            let ids = generated.clone();
+            thread::spawn(move || {
+                for &id in &ids {
+                    let decomposed = gen_clone.decompose(id);
+                    assert_eq!(decomposed.worker_id, 5);
+                    assert_eq!(decomposed, crate::decompose_snowflake(id, 1_000).unwrap());
+                }
+            })
+        })
+        .collect();
+    for reader in readers {
+        reader.join().unwrap();
+    }
+    assert_eq!(producer.join().unwrap().len(), 100_000);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_generate_ids_parallel_unique() {
+    let _serial = serial();
+    let generator = MTSG::new(0, 1).unwrap();
+    let before = generator.generate_id();
+    let ids = generator.generate_ids_parallel(1_000_000);
+    assert_eq!(ids.len(), 1_000_000);
+    assert!(before < ids[0]);
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    assert!(generator.generate_id() > ids[999_999]);
+    assert!(generator.generate_ids_parallel(0).is_empty());
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_generate_ids_parallel_with_sequence_stride() {
+    use crate::SnowflakeBuilder;
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let generator = SnowflakeBuilder::new().sequence_stride(8, 5).clock(clock.clone()).build_sync().unwrap();
+    let ids = generator.generate_ids_parallel(512);
+    assert!(ids.windows(2).all(|w| w[1] - w[0] == 8));
+    assert!(ids.iter().all(|&id| generator.decompose(id).sequence % 8 == 5));
+    assert!(matches!(generator.try_generate_id(), Err(SnowflakeError::SequenceExhausted)));
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_generate_ids_parallel_ranges() {
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let generator = MTSG::with_clock(0, 1, clock.clone()).unwrap();
+    let _ = generator.generate_id();
+    let ids = generator.generate_ids_parallel(4_095);
+    assert_eq!(generator.decompose(ids[0]).sequence, 1);
+    assert_eq!(generator.decompose(ids[4_094]).sequence, 4_095);
+    assert!(matches!(generator.try_generate_id(), Err(SnowflakeError::SequenceExhausted)));
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_new_random_worker() {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    let _serial = serial();
+    let expected = 600 + StdRng::seed_from_u64(7).random_range(0..100);
+    let generator = MTSG::new_random_worker(0, &mut StdRng::seed_from_u64(7), 600..700).unwrap();
+    let id = generator.generate_id();
+    let worker_id = generator.decompose(id).worker_id;
+    assert_eq!(worker_id, expected);
+    assert!((600..700).contains(&worker_id));
+
+    let mut rng = StdRng::seed_from_u64(7);
+    assert!(matches!(MTSG::new_random_worker(0, &mut rng, 5..5), Err(SnowflakeError::WorkerIdOutOfRange)));
+    assert!(matches!(MTSG::new_random_worker(0, &mut rng, 1_000..1_025), Err(SnowflakeError::WorkerIdOutOfRange)));
+}
+
+#[cfg(all(feature = "rand", feature = "dedup-check"))]
+#[test]
+fn test_new_random_worker_retries_duplicates() {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    let _serial = serial();
+    let mut rng = StdRng::seed_from_u64(7);
+    let first = MTSG::new_random_worker(0, &mut rng, 700..702).unwrap();
+    let second = MTSG::new_random_worker(0, &mut rng, 700..702).unwrap();
+    let (first_id, second_id) = (first.generate_id(), second.generate_id());
+    assert_ne!(first.decompose(first_id).worker_id, second.decompose(second_id).worker_id);
+    assert!(matches!(MTSG::new_random_worker(0, &mut rng, 700..702), Err(SnowflakeError::DuplicateWorkerId)));
+}
+
+#[cfg(feature = "dedup-check")]
+#[test]
+fn test_duplicate_worker_id_rejected() {
+    let _serial = serial();
+    let first = DefaultSnowflake::new(0, 77).unwrap();
+    assert!(matches!(DefaultSnowflake::new(0, 77), Err(SnowflakeError::DuplicateWorkerId)));
+    assert!(matches!(MTSG::new(0, 77), Err(SnowflakeError::DuplicateWorkerId)));
+    let _other = DefaultSnowflake::new(0, 78).unwrap();
+    drop(first);
+    assert!(DefaultSnowflake::new(0, 77).is_ok());
+
+    // Every constructor registers, including the builder and the atomic generator.
+    use crate::SnowflakeBuilder;
+    let clock = MockClock::new(10_000);
+    let built = SnowflakeBuilder::new().worker_id(79).clock(clock.clone()).build_sync().unwrap();
+    assert!(matches!(MTAtG::with_clock(0, 79, clock.clone()), Err(SnowflakeError::DuplicateWorkerId)));
+    drop(built);
+    let atomic = MTAtG::with_clock(0, 79, clock.clone()).unwrap();
+    assert!(matches!(
+        SnowflakeBuilder::new().worker_id(79).clock(clock.clone()).build_single_thread(),
+        Err(SnowflakeError::DuplicateWorkerId)
+    ));
+    drop(atomic);
+
+    // Disjoint sequence strides share a worker ID, overlapping ones do not.
+    let even = SnowflakeBuilder::new().worker_id(79).sequence_stride(2, 0).clock(clock.clone()).build_sync().unwrap();
+    let _odd = SnowflakeBuilder::new().worker_id(79).sequence_stride(4, 1).clock(clock.clone()).build_sync().unwrap();
+    assert!(matches!(
+        SnowflakeBuilder::new().worker_id(79).sequence_stride(4, 2).clock(clock.clone()).build_sync(),
+        Err(SnowflakeError::DuplicateWorkerId)
+    ));
+    drop(even);
+    assert!(SnowflakeBuilder::new().worker_id(79).sequence_stride(4, 2).clock(clock.clone()).build_sync().is_ok());
+
+    // The 128-bit generators only clash with each other.
+    use crate::extended::SnowflakeGenerator as ExtG;
+    let extended = ExtG::with_clock(0, 79, clock.clone()).unwrap();
+    assert!(matches!(ExtG::with_clock(0, 79, clock.clone()), Err(SnowflakeError::DuplicateWorkerId)));
+    drop(extended);
+    assert!(ExtG::with_clock(0, 79, clock).is_ok());
+}
+
+#[cfg(feature = "dedup-check")]
+#[test]
+fn test_duplicate_worker_id_rejected_across_threads() {
+    let _serial = serial();
+    let first = MTSG::new(0, 80).unwrap();
+    let second = std::thread::spawn(|| MTSG::new(0, 80).map(drop)).join().unwrap();
+    assert_eq!(second, Err(SnowflakeError::DuplicateWorkerId));
+    let atomic = std::thread::spawn(|| MTAtG::new(0, 80).map(drop)).join().unwrap();
+    assert_eq!(atomic, Err(SnowflakeError::DuplicateWorkerId));
+
+    // Released once the generator is dropped, whichever thread acquires it next.
+    drop(first);
+    let handle = std::thread::spawn(|| MTSG::new(0, 80).unwrap());
+    let moved = handle.join().unwrap();
+    assert!(matches!(MTSG::new(0, 80), Err(SnowflakeError::DuplicateWorkerId)));
+    drop(moved);
+    assert!(MTSG::new(0, 80).is_ok());
+}
+
+#[test]
+fn test_stats_counts_rollovers() {
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let generator = STSG::with_clock(0, 1, clock.clone()).unwrap();
+    for _ in 0..4_096 {
+        let _ = generator.generate_id();
+    }
+    let stats = generator.stats();
+    assert_eq!(stats.ids_generated, 4_096);
+    assert_eq!(stats.rollovers, 1);
+    assert!(matches!(generator.try_generate_id(), Err(SnowflakeError::SequenceExhausted)));
+    assert_eq!(generator.stats().rollovers, 1);
+    clock.advance(1);
+    let _ = generator.generate_id();
+    assert_eq!(
+        generator.stats(),
+        crate::SnowflakeStats { ids_generated: 4_097, rollovers: 1, drift_ms: 0, forward_jumps: 0, ..Default::default() }
+    );
+    drop(generator);
+
+    let mut state = DefaultSnowflake::with_clock(0, 1, clock.clone()).unwrap();
+    for _ in 0..10_000 {
+        let _ = state.generate_id_monotonic();
+    }
+    let stats = state.stats();
+    assert_eq!((stats.rollovers, stats.drift_ms), (2, 2));
+    drop(state);
+
+    // Waiting for the next millisecond counts once, however many times it slept.
+    let mut state = DefaultSnowflake::with_clock(0, 1, clock.clone()).unwrap();
+    for _ in 0..4_096 {
+        let _ = state.generate_id();
+    }
+    assert_eq!(state.stats().waits, 0);
+    let ticker = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        clock.advance(10);
+    });
+    let _ = state.generate_id();
+    ticker.join().unwrap();
+    let stats = state.stats();
+    assert_eq!(stats.waits, 1);
+    assert!(stats.time_waited >= std::time::Duration::from_millis(20));
+}
+
+#[test]
+fn test_drift_ms_decays_after_burst() {
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let mut state = DefaultSnowflake::with_clock(0, 1, clock.clone()).unwrap();
+    let _ = state.generate_id();
+    assert_eq!(state.drift_ms(), 0);
+
+    // A burst of 4 milliseconds' worth of IDs borrows the next 3 milliseconds.
+    for _ in 1..4 * 4_096 {
+        let _ = state.generate_id_monotonic();
+    }
+    let generator = STSG::from_state(state);
+    assert_eq!(generator.drift_ms(), 3);
+    clock.advance(2);
+    assert_eq!(generator.drift_ms(), 1);
+    clock.advance(3);
+    assert_eq!(generator.drift_ms(), -2);
+    assert_eq!(generator.stats().drift_ms, 0);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_stats_on_multi_thread_generators() {
+    let _serial = serial_async().await;
+    let sync = MTSG::new(0, 1).unwrap();
+    let _ = sync.generate_ids(10);
+    assert_eq!(sync.stats().ids_generated, 10);
+    let asynchronous = MTAG::new(0, 2).unwrap();
+    let _ = asynchronous.generate_ids(10).await;
+    assert_eq!(asynchronous.stats().await.ids_generated, 10);
+}
+
+#[test]
+fn test_validate_id() {
+    use crate::{min_id_for_timestamp, validate_id, validate_id_with_tolerance};
+    let _serial = serial();
+    let epoch = 1_420_070_400_000;
+    let generator = STSG::new(epoch, 12).unwrap();
+    let id = generator.generate_id();
+    assert_eq!(validate_id(id, epoch, 15).unwrap(), generator.decompose(id));
+    assert!(matches!(validate_id(id, epoch, 11), Err(SnowflakeError::WorkerIdOutOfRange)));
+    assert!(matches!(validate_id(-id, epoch, 15), Err(SnowflakeError::NegativeId)));
+
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis() as i64;
+    let ahead = min_id_for_timestamp(now + 10_000, epoch);
+    assert!(validate_id(ahead, epoch, 0).is_ok());
+    assert!(matches!(validate_id_with_tolerance(ahead, epoch, 0, 1_000), Err(SnowflakeError::TimestampInFuture)));
+    let far_ahead = min_id_for_timestamp(now + 3_600_000, epoch);
+    assert!(matches!(validate_id(far_ahead, epoch, 0), Err(SnowflakeError::TimestampInFuture)));
+}
+
+#[test]
+fn test_quick_constructors() {
+    use crate::extended::SnowflakeGenerator as ExtG;
+    let _serial = serial();
+    let now = || std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis() as i64;
+    // Epoch 0 and worker ID 0: the timestamps are plain Unix milliseconds.
+    let before = now();
+    let single = STSG::quick();
+    let decomposed = single.decompose(single.generate_id());
+    assert_eq!((single.epoch(), decomposed.worker_id, decomposed.sequence), (0, 0, 0));
+    assert!(decomposed.timestamp >= before && decomposed.timestamp <= now());
+    drop(single);
+    let multi = MTSG::quick();
+    let decomposed = multi.decompose(multi.generate_id());
+    assert_eq!((multi.epoch(), decomposed.worker_id), (0, 0));
+    assert!(decomposed.timestamp >= before && decomposed.timestamp <= now());
+    drop(multi);
+    let atomic = MTAtG::quick();
+    let decomposed = atomic.decompose(atomic.generate_id());
+    assert_eq!((atomic.epoch(), decomposed.worker_id), (0, 0));
+    assert!(decomposed.timestamp >= before && decomposed.timestamp <= now());
+    let extended = ExtG::quick();
+    let decomposed = extended.decompose(extended.generate().as_u128());
+    assert_eq!(decomposed.worker_id, 0);
+    assert!(decomposed.timestamp >= before && decomposed.timestamp <= now());
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_quick_and_default_constructors() {
+    use crate::multi_thread::async_generator::ChannelGenerator;
+    let _serial = serial_async().await;
+    let single = STSG::default();
+    assert_eq!(single.decompose(single.generate_id()).worker_id, 0);
+    drop(single);
+    let multi = MTSG::default();
+    assert_eq!(multi.decompose(multi.generate_id()).worker_id, 0);
+    drop(multi);
+    let asynchronous = MTAG::default();
+    assert_eq!(asynchronous.decompose(asynchronous.generate_id().await).await.worker_id, 0);
+    drop(asynchronous);
+    let atomic = MTAtG::default();
+    assert_eq!(atomic.decompose(atomic.generate_id()).worker_id, 0);
+    drop(atomic);
+    let channel = ChannelGenerator::quick();
+    assert_eq!(channel.generate().await.unwrap().decompose(0).unwrap().worker_id, 0);
+}
+
+#[cfg(feature = "async-std")]
+#[test]
+fn test_async_std_generator_concurrent() {
+    use crate::multi_thread::async_std_generator::SnowflakeGenerator as AsyncStdG;
+    let _serial = serial();
+    async_std::task::block_on(async {
+        let generator = AsyncStdG::new(0, 3).unwrap();
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let gen_clone = generator.clone();
+                async_std::task::spawn(async move {
+                    let mut ids = Vec::new();
+                    for _ in 0..1_000 {
+                        ids.push(gen_clone.generate_id().await);
+                    }
+                    ids
+                })
+            })
+            .collect();
+        let mut all = Vec::new();
+        for handle in handles {
+            let ids = handle.await;
+            assert!(ids.windows(2).all(|w| w[0] < w[1]));
+            all.extend(ids);
+        }
+        all.sort_unstable();
+        all.dedup();
+        assert_eq!(all.len(), 8_000);
+        assert_eq!(generator.decompose(all[0]).await.worker_id, 3);
+        assert_eq!(generator.generate_ids(5_000).await.len(), 5_000);
+    });
+}
+
+#[test]
+fn test_looks_like_snowflake() {
+    use crate::{looks_like_snowflake, min_id_for_timestamp};
+    let _serial = serial();
+    let epoch = 1_420_070_400_000;
+    let generator = STSG::new(epoch, 1).unwrap();
+    assert!(looks_like_snowflake(generator.generate_id(), epoch));
+    assert!(!looks_like_snowflake(12_345, epoch));
+    assert!(!looks_like_snowflake(-1, epoch));
+    assert!(!looks_like_snowflake(min_id_for_timestamp(epoch + (1 << 40), epoch), epoch));
+}
+
+#[test]
+fn test_sequence_stride() {
+    use crate::SnowflakeBuilder;
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let generator = SnowflakeBuilder::new().worker_id(1).sequence_stride(4, 2).clock(clock.clone()).build_single_thread().unwrap();
+    // 1024 IDs per millisecond with a step of 4.
+    let mut ids: Vec<i64> = generator.iter().take(1_024).collect();
+    assert!(matches!(generator.try_generate_id(), Err(SnowflakeError::SequenceExhausted)));
+    assert_eq!(generator.decompose(generator.peek_id()), crate::SnowflakeDecomposed { timestamp: 10_001, worker_id: 1, sequence: 2 });
+    clock.advance(1);
+    ids.extend(generator.iter().take(1_024));
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    assert!(ids.iter().all(|&id| generator.decompose(id).sequence % 4 == 2));
+    assert_eq!(generator.decompose(ids[0]).sequence, 2);
+    assert_eq!(generator.decompose(ids[1_023]).sequence, 4_094);
+
+    let other = SnowflakeBuilder::new().worker_id(1).sequence_stride(4, 3).clock(clock.clone()).build_sync().unwrap();
+    let other_ids: Vec<i64> = (0..100).map(|_| other.generate_id()).collect();
+    assert!(other_ids.iter().all(|id| !ids.contains(id)));
+
+    for (step, offset) in [(0, 0), (3, 0), (4, 4), (8192, 0)] {
+        assert!(
+            matches!(DefaultSnowflake::new(0, 2).unwrap().sequence_stride(step, offset), Err(SnowflakeError::InvalidSequenceStride)),
+            "{} {}",
+            step,
+            offset
+        );
+    }
+}
+
+#[test]
+fn test_randomize_initial_sequence() {
+    use crate::SnowflakeBuilder;
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let start = |seed: u64| {
+        let mut state = DefaultSnowflake::with_clock(0, 1, clock.clone()).unwrap();
+        state.randomize_initial_sequence(seed);
+        state.current_sequence()
+    };
+    assert_eq!(start(1), start(1));
+    assert_ne!(start(1), start(2));
+    let starts: std::collections::HashSet<u16> = (0..100).map(start).collect();
+    assert!(starts.len() > 90);
+    assert!(starts.iter().all(|&sequence| sequence <= 0xFFF));
+
+    // Never moves backwards within a millisecond, and later milliseconds start at 0.
+    let mut state = DefaultSnowflake::with_clock(0, 1, clock.clone()).unwrap();
+    let mut ids: Vec<i64> = (0..4_000).map(|_| state.generate_id()).collect();
+    state.randomize_initial_sequence(1);
+    assert!(state.current_sequence() >= 4_000);
+    while let Ok(id) = state.try_generate_id() {
+        ids.push(id);
+    }
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    clock.advance(1);
+    let id = state.generate_id();
+    assert_eq!(state.decompose(id).sequence, 0);
+
+    let generator = SnowflakeBuilder::new().sequence_stride(4, 3).randomize_initial_sequence(7).clock(clock).build_sync().unwrap();
+    let sequence = generator.decompose(generator.generate_id()).sequence;
+    assert_eq!(sequence % 4, 3);
+}
+
+#[test]
+fn test_generate_decompose_round_trip_fuzz() {
+    let _serial = serial();
+    // Deterministic xorshift so failures can be reproduced.
+    let mut seed: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut next = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed
+    };
+    for _ in 0..200 {
+        let epoch = (next() % (1 << 40)) as i64;
+        let worker_id = (next() % 1_024) as u16;
+        let start = epoch + (next() % (1 << 40)) as i64;
+        let clock = MockClock::new(start);
+        let mut state = DefaultSnowflake::with_clock(epoch, worker_id, clock.clone()).unwrap();
+        let mut last = -1;
+        for _ in 0..50 {
+            if next() % 4 == 0 {
+                clock.advance((next() % 3) as i64);
+            }
+            let Ok(id) = state.try_generate_id() else {
+                clock.advance(1);
+                continue;
+            };
+            assert!(id > last);
+            last = id;
+            let decomposed = crate::decompose_snowflake(id, epoch).unwrap();
+            assert_eq!(decomposed.worker_id, worker_id);
+            assert!(decomposed.sequence <= 0xFFF);
+            assert!(decomposed.timestamp >= start && decomposed.timestamp <= clock.now_millis());
+            assert_eq!(decomposed, state.decompose(id));
+        }
+        // Drain the rest of the current millisecond to cover the end of the sequence space.
+        let mut max_sequence = 0;
+        while let Ok(id) = state.try_generate_id() {
+            let decomposed = crate::decompose_snowflake(id, epoch).unwrap();
+            assert_eq!(decomposed.worker_id, worker_id);
+            max_sequence = decomposed.sequence;
+        }
+        assert_eq!(max_sequence, 0xFFF);
+    }
+}
+
+#[test]
+fn test_generate_id_strings() {
+    use crate::encoding::decode_base62;
+    let _serial = serial();
+    let single = STSG::quick();
+    let decimal = single.generate_id_string();
+    let base62 = single.generate_id_base62_string();
+    let (decimal, base62) = (decimal.parse::<i64>().unwrap(), decode_base62(&base62).unwrap());
+    assert!(decimal < base62);
+    assert_eq!(single.decompose(base62).worker_id, 0);
+
+    let multi = MTSG::new(0, 3).unwrap();
+    let id: i64 = multi.generate_id_string().parse().unwrap();
+    assert_eq!(multi.decompose(id).worker_id, 3);
+    let id = decode_base62(&multi.generate_id_base62_string()).unwrap();
+    assert_eq!(multi.decompose(id).worker_id, 3);
+
+    let atomic = MTAtG::new(0, 4).unwrap();
+    let id: i64 = atomic.generate_id_string().parse().unwrap();
+    assert_eq!(atomic.decompose(id).worker_id, 4);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_async_generate_id_strings() {
+    use crate::encoding::decode_base62;
+    let _serial = serial_async().await;
+    let generator = MTAG::new(0, 5).unwrap();
+    let id: i64 = generator.generate_id_string().await.parse().unwrap();
+    assert_eq!(generator.decompose(id).await.worker_id, 5);
+    let id = decode_base62(&generator.generate_id_base62_string().await).unwrap();
+    assert_eq!(generator.decompose(id).await.worker_id, 5);
+}
+
+#[test]
+fn test_generate_with_decomposed() {
+    let _serial = serial();
+    let clock = MockClock::new(5_000);
+    let generator = MTSG::with_clock(1_000, 9, clock.clone()).unwrap();
+    let _ = generator.generate_id();
+    let (id, decomposed) = generator.generate_with_decomposed();
+    assert_eq!(decomposed, generator.decompose(id));
+    assert_eq!(decomposed, crate::SnowflakeDecomposed { timestamp: 5_000, worker_id: 9, sequence: 1 });
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_async_generate_with_decomposed() {
+    let _serial = serial_async().await;
+    let generator = MTAG::new(1_000, 6).unwrap();
+    let (id, decomposed) = generator.generate_with_decomposed().await;
+    assert_eq!(decomposed, generator.decompose(id).await);
+    assert_eq!(decomposed.worker_id, 6);
+}
+
+#[test]
+fn test_backoff_strategies() {
+    use crate::BackoffStrategy;
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let generator = MTSG::with_clock(0, 1, clock.clone()).unwrap();
+    generator.set_backoff_strategy(BackoffStrategy::Error);
+    for _ in 0..4_096 {
+        generator.generate_id_with_backoff().unwrap();
+    }
+    assert!(matches!(generator.generate_id_with_backoff(), Err(SnowflakeError::SequenceExhausted)));
+    clock.set(9_999);
+    assert!(matches!(generator.generate_id_with_backoff(), Err(SnowflakeError::ClockMovedBackwards { by_ms: 1 })));
+    clock.set(10_000);
+
+    for backoff in [BackoffStrategy::SpinYield, BackoffStrategy::SleepMs] {
+        generator.set_backoff_strategy(backoff);
+        let advancer = {
+            let clock = clock.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(std::time::Duration::from_millis(20));
+                clock.advance(1);
+            })
+        };
+        // Exhausted until the other thread advances the clock.
+        let id = generator.generate_id_with_backoff().unwrap();
+        advancer.join().unwrap();
+        assert_eq!(generator.decompose(id).timestamp, clock.now_millis());
+        while generator.try_generate_id().is_ok() {}
+    }
+
+    // `generate_id` cannot fail, so it waits even with `BackoffStrategy::Error`.
+    generator.set_backoff_strategy(BackoffStrategy::Error);
+    let advancer = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        clock.advance(1);
+    });
+    let _ = generator.generate_id();
+    advancer.join().unwrap();
+}
+
+#[test]
+fn test_backoff_strategy_per_generator() {
+    use crate::{BackoffStrategy, SnowflakeBuilder};
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let builder = SnowflakeBuilder::new().clock(clock.clone());
+    let interactive = builder.clone().worker_id(314).backoff_strategy(BackoffStrategy::Error).build_sync().unwrap();
+    let batch = builder.worker_id(315).backoff_strategy(BackoffStrategy::SpinYield).build_sync().unwrap();
+    while interactive.try_generate_id().is_ok() {}
+    while batch.try_generate_id().is_ok() {}
+
+    assert!(matches!(interactive.generate_id_with_backoff(), Err(SnowflakeError::SequenceExhausted)));
+    let advancer = {
+        let clock = clock.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            clock.advance(1);
+        })
+    };
+    // Only the batch generator waits for the next millisecond.
+    let id = batch.generate_id_with_backoff().unwrap();
+    advancer.join().unwrap();
+    assert_eq!(batch.decompose(id).timestamp, 10_001);
+    assert!(interactive.generate_id_with_backoff().is_ok());
+}
+
+#[test]
+fn test_generate_id_after() {
+    use crate::raw::{FieldOrder, RawSnowflake};
+    use crate::BackoffStrategy;
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let generator = MTSG::with_clock(0, 316, clock.clone()).unwrap();
+    let floor = generator.generate_id();
+    assert!(generator.generate_id_after(floor).unwrap() > floor);
+
+    // A floor 5 ms ahead of the clock is only reached once the clock catches up.
+    let ahead = (10_005 << 22) | 4_095;
+    generator.set_backoff_strategy(BackoffStrategy::Error);
+    assert!(matches!(generator.generate_id_after(ahead), Err(SnowflakeError::TimestampInFuture)));
+    clock.advance(5);
+    let id = generator.generate_id_after(ahead).unwrap();
+    assert!(id > ahead);
+    assert_eq!(generator.decompose(id).timestamp, 10_005);
+
+    // In the millisecond of the floor, the sequence numbers below it are skipped, not generated.
+    let generated = generator.stats().ids_generated;
+    let same_worker = (10_005 << 22) | (316 << 12) | 100;
+    let id = generator.generate_id_after(same_worker).unwrap();
+    assert_eq!(generator.decompose(id).sequence, 101);
+    assert_eq!(generator.stats().ids_generated, generated + 1);
+
+    // No ID of worker 316 exceeds one of worker 1023 in the same millisecond.
+    let higher_worker = (10_005 << 22) | (1_023 << 12);
+    assert!(matches!(generator.generate_id_after(higher_worker), Err(SnowflakeError::TimestampInFuture)));
+    assert_eq!(generator.stats().ids_generated, generated + 1);
+    assert_eq!(generator.decompose(generator.generate_id()).sequence, 102);
+    generator.set_backoff_strategy(BackoffStrategy::SpinYield);
+    let advancer = {
+        let clock = clock.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            clock.advance(1);
+        })
+    };
+    let id = generator.generate_id_after(higher_worker).unwrap();
+    advancer.join().unwrap();
+    assert!(id > higher_worker);
+    assert_eq!(generator.decompose(id).timestamp, 10_006);
+
+    let far_ahead = (10_006 + crate::DEFAULT_MAX_FUTURE_MS + 1) << 22;
+    assert!(matches!(generator.generate_id_after(far_ahead), Err(SnowflakeError::TimestampInFuture)));
+
+    // The skipped sequence numbers respect the stride and the field order.
+    let mut raw = RawSnowflake::<41, 10, 12>::new(0, 5, 10).unwrap().with_sequence_stride(4, 1).unwrap();
+    let floor = (10 << 22) | (5 << 12) | 6;
+    let id = raw.generate_id_after(10, floor).unwrap();
+    assert!(id > floor);
+    assert_eq!(raw.decompose(id).sequence, 9);
+    let mut raw = RawSnowflake::<41, 10, 12>::new(0, 5, 10).unwrap().with_field_order(FieldOrder::SequenceThenWorker);
+    for (floor, sequence) in [((10 << 22) | (7 << 10) | 4, 7), ((10 << 22) | (20 << 10) | 9, 21)] {
+        let id = raw.generate_id_after(10, floor).unwrap();
+        assert!(id > floor);
+        assert_eq!(raw.decompose(id).sequence, sequence);
+    }
+    assert_eq!(raw.generate_id_after(10, i64::MAX), Err(SnowflakeError::TimestampOverflow));
+}
+
+#[test]
+fn test_monotonic_only_clock_mode_never_repeats() {
+    use crate::ClockMode;
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let mut state = DefaultSnowflake::with_clock(0, 1, clock.clone()).unwrap();
+    state.set_clock_mode(ClockMode::MonotonicOnly);
+    let mut timestamps = Vec::new();
+    for _ in 0..50 {
+        clock.advance(1);
+        // Falling behind the wall-clock is not corrected, so the time never jumps.
+        clock.drift(1);
+        state.resync().unwrap();
+        clock.advance(1);
+        let id = state.generate_id();
+        timestamps.push(state.decompose(id).timestamp);
+    }
+    assert!(timestamps.windows(2).all(|w| w[1] == w[0] + 1));
+    assert_eq!(clock.now_millis(), 10_050);
+
+    clock.drift(crate::DEFAULT_RESYNC_THRESHOLD_MS);
+    state.resync().unwrap();
+    assert_eq!(clock.now_millis(), 10_100 - 50 - crate::DEFAULT_RESYNC_THRESHOLD_MS);
+    state.set_clock_mode(ClockMode::WallClock);
+    state.resync().unwrap();
+    assert_eq!(clock.now_millis(), 10_100);
+}
+
+#[test]
+fn test_forward_jump_policies() {
+    use crate::ForwardJumpPolicy;
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let mut state = DefaultSnowflake::with_clock(0, 1, clock.clone()).unwrap();
+    state.set_forward_jump_policy(ForwardJumpPolicy::Count { max_ms: 1_000 });
+    let _ = state.generate_id();
+    clock.advance(500);
+    let _ = state.generate_id();
+    clock.advance(10_000);
+    let id = state.generate_id();
+    assert_eq!(state.decompose(id).timestamp, 20_500);
+    assert_eq!(state.stats().forward_jumps, 1);
+
+    // Clamping advances by at most 1 second per ID, so a prompt correction costs little.
+    state.set_forward_jump_policy(ForwardJumpPolicy::Clamp { max_ms: 1_000 });
+    clock.advance(10_000);
+    let mut timestamps = Vec::new();
+    let mut last = 0;
+    for _ in 0..3 {
+        last = state.generate_id();
+        timestamps.push(state.decompose(last).timestamp);
+    }
+    assert_eq!(timestamps, vec![21_500, 22_500, 23_500]);
+    assert_eq!(state.peek_id() >> 22, 24_500);
+    assert_eq!(state.stats().forward_jumps, 2);
+
+    clock.set(23_500);
+    let after_correction = state.generate_id();
+    assert!(after_correction > last);
+    assert_eq!(state.decompose(after_correction).timestamp, 23_500);
+    assert_eq!(state.stats().forward_jumps, 2);
+}
+
+#[test]
+fn test_resync_corrects_drift_monotonically() {
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let mut state = DefaultSnowflake::with_clock(0, 1, clock.clone()).unwrap();
+    let before = state.generate_id();
+
+    // Within the threshold, the drift is left alone.
+    clock.advance(5_000);
+    clock.drift(crate::DEFAULT_RESYNC_THRESHOLD_MS);
+    state.resync().unwrap();
+    assert_eq!(clock.now_millis(), 15_000 - crate::DEFAULT_RESYNC_THRESHOLD_MS);
+
+    clock.drift(2_000);
+    state.resync().unwrap();
+    assert_eq!(clock.now_millis(), 15_000);
+    let after = state.generate_id();
+    assert!(after > before);
+    assert_eq!(state.decompose(after).timestamp, 15_000);
+
+    // A fresh system clock is already in sync and never moves backwards.
+    let mut system = crate::SystemClock::new().unwrap();
+    let now = system.now_millis();
+    system.resync(crate::DEFAULT_RESYNC_THRESHOLD_MS).unwrap();
+    assert!(system.now_millis() >= now);
+}
+
+#[test]
+fn test_snapshot_round_trip() {
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let mut state = DefaultSnowflake::with_clock(1_000, 42, clock.clone()).unwrap();
+    let last = (0..10).map(|_| state.generate_id()).last().unwrap();
+    let bytes = state.to_bytes();
+    drop(state);
+
+    // Restarted within the same millisecond.
+    let mut restored = DefaultSnowflake::from_bytes_with_clock(&bytes, clock.clone()).unwrap();
+    assert_eq!(restored.to_bytes(), bytes);
+    assert_eq!((restored.epoch, restored.worker_id, restored.current_sequence()), (1_000, 42, 10));
+    let next = restored.generate_id();
+    assert!(next > last);
+    assert_eq!(restored.decompose(next).sequence, 10);
+    drop(restored);
+
+    // Restarted with the clock behind the snapshot.
+    clock.set(9_999);
+    let mut restored = DefaultSnowflake::from_bytes_with_clock(&bytes, clock.clone()).unwrap();
+    assert!(matches!(restored.try_generate_id(), Err(SnowflakeError::ClockMovedBackwards { by_ms: 1 })));
+    drop(restored);
+
+    let restored = DefaultSnowflake::from_bytes(&bytes).unwrap();
+    assert_eq!(restored.time_since_epoch, 9_000);
+}
+
+#[test]
+fn test_remaining_in_window() {
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let generator = MTSG::with_clock(0, 1, clock.clone()).unwrap();
+    assert_eq!(generator.remaining_in_window(), 4_096);
+    for _ in 0..10 {
+        let _ = generator.generate_id();
+    }
+    assert_eq!(generator.remaining_in_window(), 4_086);
+    clock.advance(1);
+    assert_eq!(generator.remaining_in_window(), 4_096);
+    clock.set(9_999);
+    assert_eq!(generator.remaining_in_window(), 0);
+    clock.set(10_000);
+    while generator.try_generate_id().is_ok() {}
+    assert_eq!(generator.remaining_in_window(), 0);
+
+    let mut strided = DefaultSnowflake::with_clock(0, 2, clock.clone()).unwrap().sequence_stride(4, 1).unwrap();
+    assert_eq!(strided.remaining_in_window(), 1_024);
+    let _ = strided.generate_id();
+    assert_eq!(strided.remaining_in_window(), 1_023);
+
+    let atomic = MTAtG::with_clock(0, 3, clock.clone()).unwrap();
+    let _ = atomic.generate_id();
+    let _ = atomic.generate_id();
+    assert_eq!(atomic.remaining_in_window(), 4_094);
+    clock.advance(1);
+    assert_eq!(atomic.remaining_in_window(), 4_096);
+    clock.set(9_999);
+    assert_eq!(atomic.remaining_in_window(), 0);
+    clock.set(10_000);
+    while atomic.peek_id() >> 22 == 10_000 {
+        let _ = atomic.generate_id();
+    }
+    assert_eq!(atomic.remaining_in_window(), 0);
+}
+
+#[test]
+fn test_single_thread_try_into_inner() {
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let generator = STSG::with_clock(0, 3, clock).unwrap();
+    let last = generator.generate_id();
+    let clone = generator.clone();
+    let generator = generator.try_into_inner().err().expect("a clone is alive");
+    drop(clone);
+
+    let mut state = generator.try_into_inner().ok().expect("no clone is alive");
+    assert!(state.generate_id() > last);
+    assert_eq!(state.worker_id, 3);
+}
+
+#[test]
+fn test_snapshot_invalid_bytes() {
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let bytes = DefaultSnowflake::with_clock(0, 1, clock.clone()).unwrap().to_bytes();
+    assert!(matches!(DefaultSnowflake::from_bytes_with_clock(&bytes[..10], clock.clone()), Err(SnowflakeError::InvalidSnapshot)));
+    let mut wrong_version = bytes;
+    wrong_version[0] = 0;
+    assert!(matches!(DefaultSnowflake::from_bytes_with_clock(&wrong_version, clock.clone()), Err(SnowflakeError::InvalidSnapshot)));
+    assert!(matches!(
+        SnowflakeState::<39, 14, 10, MockClock>::from_bytes_with_clock(&bytes, clock.clone()),
+        Err(SnowflakeError::InvalidSnapshot)
+    ));
+    let mut bad_worker = bytes;
+    bad_worker[12..14].copy_from_slice(&1_024u16.to_le_bytes());
+    assert!(matches!(DefaultSnowflake::from_bytes_with_clock(&bad_worker, clock.clone()), Err(SnowflakeError::InvalidSnapshot)));
+    let mut bad_sequence = bytes;
+    bad_sequence[22..24].copy_from_slice(&5_000u16.to_le_bytes());
+    assert!(matches!(DefaultSnowflake::from_bytes_with_clock(&bad_sequence, clock), Err(SnowflakeError::InvalidSnapshot)));
+}
+
+#[test]
+fn bench_micros_burst_never_sleeps() {
+    use crate::{BackoffStrategy, MicrosSnowflake};
+    /// A clock moving one unit forward every `reads_per_unit` reads, like a burst of that
+    /// many IDs per unit of time.
+    #[derive(Clone)]
+    struct BurstClock {
+        reads: Arc<AtomicI64>,
+        reads_per_unit: i64,
+    }
+    impl ClockSource for BurstClock {
+        fn now_millis(&self) -> i64 {
+            10_000 + self.reads.fetch_add(1, Ordering::SeqCst) / self.reads_per_unit
+        }
+    }
+    let _serial = serial();
+    // A burst of 8192 IDs per millisecond, twice what the millisecond layout holds.
+    let ids = 200_000;
+    let clock = BurstClock { reads: Arc::new(AtomicI64::new(0)), reads_per_unit: 8_192 };
+    let mut millis = DefaultSnowflake::with_clock(0, 310, clock).unwrap();
+    millis.set_backoff_strategy(BackoffStrategy::SpinYield);
+    for _ in 0..ids {
+        let _ = millis.generate_id();
+    }
+    assert!(millis.stats().rollovers > 0);
+    assert!(millis.stats().waits > 0);
+
+    let clock = BurstClock { reads: Arc::new(AtomicI64::new(0)), reads_per_unit: 8 };
+    let mut micros = MicrosSnowflake::with_clock(0, 311, clock).unwrap();
+    for _ in 0..ids {
+        let _ = micros.generate_id();
+    }
+    assert_eq!(micros.stats().rollovers, 0);
+    assert_eq!(micros.stats().waits, 0);
+}
+
+#[test]
+fn bench_single_thread_snowflake_id_generation() {
+    let _serial = serial();
+    let generator = STSG::new(0, 1).unwrap();
+    let ids = 1_000_000;
+    let time = std::time::Instant::now();
+    for _ in 0..ids {
+        let _ = generator.generate_id();
+    }
+    let elapsed = time.elapsed();
+    let stats = generator.stats();
+    println!("Generated {} IDs in {:?}", ids, elapsed);
+    println!("({:.2} IDs/ms)", ids as f64 / elapsed.as_millis() as f64);
+    println!("{} rollover sleeps totaling {} ms", stats.waits, stats.time_waited.as_millis());
+    assert!(stats.waits <= stats.rollovers);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn bench_multi_thread_async_snowflake_id_generation() {
+    use std::thread;
+    let _serial = serial_async().await;
+    let generator = MTAG::new(0, 1).unwrap();
+    let ids_per_thread = 100_000;
+    let threads = 10;
+    let time = std::time::Instant::now();
+    let mut handles = vec![];
+    for _ in 0..threads {
+        let gen_clone = generator.clone();
+        let handle = thread::spawn(async move || {
+            for _ in 0..ids_per_thread {
+                let _ = gen_clone.generate_id().await;
+            }
+        });
+        handles.push(handle);
+    }
+    for handle in handles {
+        handle.join().unwrap().await;
+    }
+    let elapsed = time.elapsed();
+    println!("Generated {} IDs in {:?}", ids_per_thread * threads, elapsed);
+    println!("({:.2} IDs/ms)", (ids_per_thread * threads) as f64 / elapsed.as_millis() as f64);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn bench_multi_thread_async_mutex_vs_channel() {
+    use crate::multi_thread::async_generator::ChannelGenerator;
+    let _serial = serial_async().await;
+    let ids_per_task = 20_000;
+    let tasks = 10;
+
+    let mutex_generator = MTAG::new(0, 1).unwrap();
+    let time = std::time::Instant::now();
+    let handles: Vec<_> = (0..tasks)
+        .map(|_| {
+            let gen_clone = mutex_generator.clone();
+            tokio::spawn(async move {
+                for _ in 0..ids_per_task {
+                    let _ = gen_clone.generate_id().await;
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.await.unwrap();
+    }
+    let mutex_elapsed = time.elapsed();
+
+    let channel_generator = ChannelGenerator::new(0, 2).unwrap();
+    let time = std::time::Instant::now();
+    let handles: Vec<_> = (0..tasks)
+        .map(|_| {
+            let gen_clone = channel_generator.clone();
+            tokio::spawn(async move {
+                for _ in 0..ids_per_task {
+                    gen_clone.generate_id().await.unwrap();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.await.unwrap();
+    }
+    let channel_elapsed = time.elapsed();
+
+    println!("Mutex: generated {} IDs in {:?}", ids_per_task * tasks, mutex_elapsed);
+    println!("Channel: generated {} IDs in {:?}", ids_per_task * tasks, channel_elapsed);
+}
+
+#[test]
+fn bench_multi_thread_sync_snowflake_id_generation() {
+    use std::thread;
+    let _serial = serial();
+    let generator = MTSG::new(0, 1).unwrap();
+    let ids_per_thread = 100_000;
+    let threads = 10;
+    let time = std::time::Instant::now();
+    let mut handles = vec![];
+    for _ in 0..threads {
+        let gen_clone = generator.clone();
+        let handle = thread::spawn(move || {
+            for _ in 0..ids_per_thread {
+                let _ = gen_clone.generate_id();
+            }
+        });
+        handles.push(handle);
+    }
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    let elapsed = time.elapsed();
+    println!("Generated {} IDs in {:?}", ids_per_thread * threads, elapsed);
+    println!("({:.2} IDs/ms)", (ids_per_thread * threads) as f64 / elapsed.as_millis() as f64);
+}
+#[test]
+fn bench_multi_thread_atomic_snowflake_id_generation() {
+    use std::thread;
+    let _serial = serial();
+    let ids_per_thread = 100_000;
+    let threads = 10;
+
+    let mutex_generator = MTSG::new(0, 1).unwrap();
+    let time = std::time::Instant::now();
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let gen_clone = mutex_generator.clone();
+            thread::spawn(move || {
+                for _ in 0..ids_per_thread {
+                    let _ = gen_clone.generate_id();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    let mutex_elapsed = time.elapsed();
+    drop(mutex_generator);
+
+    let atomic_generator = MTAtG::new(0, 1).unwrap();
+    let time = std::time::Instant::now();
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let gen_clone = atomic_generator.clone();
+            thread::spawn(move || {
+                for _ in 0..ids_per_thread {
+                    let _ = gen_clone.generate_id();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    let atomic_elapsed = time.elapsed();
+
+    println!("Mutex: generated {} IDs in {:?}", ids_per_thread * threads, mutex_elapsed);
+    println!("Atomic: generated {} IDs in {:?}", ids_per_thread * threads, atomic_elapsed);
+}
+
+#[test]
+fn test_field_order_round_trip() {
+    use crate::decompose_snowflake;
+    use crate::raw::FieldOrder;
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let mut state = DefaultSnowflake::with_clock(1_000, 0x2A5, clock.clone())
+        .unwrap()
+        .field_order(FieldOrder::SequenceThenWorker);
+    let ids: Vec<i64> = (0..3).map(|_| state.generate_id()).collect();
+    assert!(ids.windows(2).all(|w| w[1] - w[0] == 1 << 10));
+    for (sequence, &id) in ids.iter().enumerate() {
+        let decomposed = state.decompose(id);
+        assert_eq!((decomposed.timestamp, decomposed.worker_id, decomposed.sequence), (10_000, 0x2A5, sequence as u16));
+        // The two layouts are not interchangeable.
+        assert_ne!(decompose_snowflake(id, 1_000).unwrap(), decomposed);
+    }
+    assert!(matches!(state.try_generate_id_with_checksum(), Err(SnowflakeError::UnsupportedFieldOrder)));
+
+    let bytes = state.to_bytes();
+    drop(state);
+    let mut restored = DefaultSnowflake::from_bytes_with_clock(&bytes, clock.clone()).unwrap();
+    assert_eq!(restored.field_order, FieldOrder::SequenceThenWorker);
+    let next = restored.generate_id();
+    assert_eq!(restored.decompose(next).sequence, 3);
+    drop(restored);
+
+    // Snapshots taken before the field order existed restore with the default order.
+    let mut v1 = DefaultSnowflake::with_clock(1_000, 0x2A5, clock.clone()).unwrap().to_bytes()[..28].to_vec();
+    v1[0] = 1;
+    let restored = DefaultSnowflake::from_bytes_with_clock(&v1, clock.clone()).unwrap();
+    assert_eq!(restored.field_order, FieldOrder::WorkerThenSequence);
+    let mut bad_order = bytes;
+    bad_order[28] = 2;
+    assert!(matches!(DefaultSnowflake::from_bytes_with_clock(&bad_order, clock), Err(SnowflakeError::InvalidSnapshot)));
+}
+
+#[test]
+fn test_builder_field_order_round_trip() {
+    use crate::raw::FieldOrder;
+    use crate::SnowflakeBuilder;
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let generator = SnowflakeBuilder::new()
+        .epoch(1_000)
+        .worker_id(0x2A6)
+        .field_order(FieldOrder::SequenceThenWorker)
+        .clock(clock)
+        .build_sync()
+        .unwrap();
+    for sequence in 0..3 {
+        let id = generator.generate_id();
+        let expected = crate::SnowflakeDecomposed { timestamp: 10_000, worker_id: 0x2A6, sequence };
+        assert_eq!(generator.decompose(id), expected);
+        assert_eq!(generator.clone().decompose(id), expected);
+        assert_eq!(generator.try_decompose(id), Ok(expected));
+    }
+    assert_eq!(generator.try_decompose(-1), Err(SnowflakeError::NegativeId));
+}
+
+#[test]
+fn test_new_unchecked_matches_new() {
+    use crate::raw::RawSnowflake;
+    let _serial = serial();
+    let mut checked = RawSnowflake::<41, 10, 12>::new(1_000, 913, 5_000).unwrap();
+    // SAFETY: the worker ID fits 10 bits and the epoch is before the timestamp.
+    let mut unchecked = unsafe { RawSnowflake::<41, 10, 12>::new_unchecked(1_000, 913, 5_000) };
+    for now in [5_000, 5_000, 5_001] {
+        assert_eq!(checked.generate_id(now), unchecked.generate_id(now));
+    }
+
+    let mut state = DefaultSnowflake::new(1_000, 913).unwrap();
+    // SAFETY: as above, and the system time is after the Unix epoch.
+    let mut unchecked = unsafe { DefaultSnowflake::new_unchecked(1_000, 913) };
+    let (id, unchecked_id) = (state.generate_id(), unchecked.generate_id());
+    let (decomposed, unchecked_decomposed) = (state.decompose(id), unchecked.decompose(unchecked_id));
+    assert_eq!((decomposed.worker_id, decomposed.sequence), (unchecked_decomposed.worker_id, unchecked_decomposed.sequence));
+    assert!((decomposed.timestamp - unchecked_decomposed.timestamp).abs() < 1_000);
+}
+
+#[test]
+fn test_decompose_discord() {
+    use crate::{decompose_discord, discord_epoch};
+    assert_eq!(discord_epoch(), 1_420_070_400_000);
+    // The example ID of the Discord API reference, created 2016-04-30 11:18:25.796 UTC.
+    let decomposed = decompose_discord(175_928_847_299_117_063).unwrap();
+    assert_eq!(decomposed.timestamp, 1_462_015_105_796);
+    assert_eq!((decomposed.datacenter_id(), decomposed.datacenter_worker_id(), decomposed.sequence), (1, 0, 7));
+    assert!(matches!(decompose_discord(-1), Err(SnowflakeError::NegativeId)));
+}
+
+#[test]
+fn test_compose_snowflake_round_trip() {
+    use crate::{compose_snowflake, decompose_snowflake, SnowflakeDecomposed, MAX_SEQUENCE, MAX_WORKER_ID};
+    let epoch = 1_288_834_974_657;
+    for id in [0, 1, 175_928_847_299_117_063, (1 << 22) | (1023 << 12) | 4095, i64::MAX] {
+        assert_eq!(compose_snowflake(&decompose_snowflake(id, epoch).unwrap(), epoch).unwrap(), id);
+    }
+
+    let valid = SnowflakeDecomposed { timestamp: epoch + 5, worker_id: 7, sequence: 9 };
+    assert_eq!(compose_snowflake(&valid, epoch).unwrap(), (5 << 22) | (7 << 12) | 9);
+    let before = SnowflakeDecomposed { timestamp: epoch - 1, ..valid };
+    assert!(matches!(compose_snowflake(&before, epoch), Err(SnowflakeError::TimestampBeforeEpoch)));
+    let too_late = SnowflakeDecomposed { timestamp: epoch + (1 << 41), ..valid };
+    assert!(matches!(compose_snowflake(&too_late, epoch), Err(SnowflakeError::TimestampOverflow)));
+    let worker = SnowflakeDecomposed { worker_id: MAX_WORKER_ID + 1, ..valid };
+    assert!(matches!(compose_snowflake(&worker, epoch), Err(SnowflakeError::WorkerIdOutOfRange)));
+    let sequence = SnowflakeDecomposed { sequence: MAX_SEQUENCE + 1, ..valid };
+    assert!(matches!(compose_snowflake(&sequence, epoch), Err(SnowflakeError::SequenceOutOfRange)));
+    assert!(matches!(compose_snowflake(&valid, i64::MIN), Err(SnowflakeError::TimestampOverflow)));
+}
+
+#[test]
+fn test_reserve_block() {
+    use crate::BackoffStrategy;
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let generator = MTSG::with_clock(0, 1, clock.clone()).unwrap();
+    let before = generator.generate_id();
+    let block = generator.reserve(100).unwrap();
+    let ids: Vec<i64> = block.into_iter().collect();
+    assert_eq!(ids.len(), 100);
+    assert_eq!((ids[0], block.first(), block.last()), (before + 1, before + 1, Some(before + 100)));
+    assert!(ids.windows(2).all(|w| w[1] == w[0] + 1));
+    assert!(ids.iter().all(|&id| block.contains(id)));
+    assert!(!block.contains(before) && !block.contains(before + 101));
+    assert_eq!(generator.generate_id(), before + 101);
+    assert_eq!(generator.stats().ids_generated, 102);
+
+    // 3,994 IDs are left in this millisecond.
+    generator.set_backoff_strategy(BackoffStrategy::Error);
+    assert!(matches!(generator.reserve(4_000), Err(SnowflakeError::SequenceExhausted)));
+    assert!(matches!(generator.reserve(4_097), Err(SnowflakeError::BlockTooLarge)));
+    clock.advance(1);
+    let block = generator.reserve(4_096).unwrap();
+    assert_eq!(generator.decompose(block.first()).timestamp, 10_001);
+    assert!(block.first() > before + 101);
+    assert_eq!(block.iter().count(), 4_096);
+    assert!(generator.reserve(0).unwrap().is_empty());
+
+    // With a stride, the IDs of a block are evenly spaced.
+    let mut state = DefaultSnowflake::with_clock(0, 2, clock).unwrap().sequence_stride(4, 1).unwrap();
+    let ids: Vec<i64> = state.reserve(3).unwrap().into_iter().collect();
+    assert!(ids.windows(2).all(|w| w[1] == w[0] + 4));
+    assert_eq!(state.decompose(ids[0]).sequence, 1);
+    assert!(matches!(state.reserve(1_025), Err(SnowflakeError::BlockTooLarge)));
+}
+
+#[test]
+fn test_persistent_generator_saves_on_drop() {
+    use crate::multi_thread::sync_generator::PersistentGenerator;
+    use std::sync::Mutex;
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let saved = Arc::new(Mutex::new(Vec::new()));
+    let sink = {
+        let saved = saved.clone();
+        move |bytes: &[u8]| saved.lock().unwrap().push(bytes.to_vec())
+    };
+    let generator = PersistentGenerator::new(MTSG::with_clock(1_000, 42, clock.clone()).unwrap(), sink);
+    let ids = generator.generate_ids(10);
+    assert!(saved.lock().unwrap().is_empty());
+    drop(generator);
+
+    let saved = saved.lock().unwrap();
+    assert_eq!(saved.len(), 1);
+    let mut restored = DefaultSnowflake::from_bytes_with_clock(&saved[0], clock).unwrap();
+    assert_eq!((restored.epoch, restored.worker_id, restored.current_sequence()), (1_000, 42, 10));
+    assert!(restored.generate_id() > ids[9]);
+}
+
+#[test]
+fn test_shard_of_uniform() {
+    use crate::shard_of;
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let generator = MTSG::with_clock(0, 1, clock.clone()).unwrap();
+    let mut counts = [0u32; 16];
+    for _ in 0..100 {
+        for id in generator.generate_ids(1_000) {
+            counts[shard_of(id, 16) as usize] += 1;
+        }
+        clock.advance(1);
+    }
+    // 6,250 IDs per shard on average.
+    assert!(counts.iter().all(|&count| (5_900..6_600).contains(&count)), "{:?}", counts);
+
+    // The worker ID does not change the shard.
+    let other = MTSG::with_clock(0, 2, clock.clone()).unwrap();
+    let (id, other_id) = (generator.generate_id(), other.generate_id());
+    assert_eq!(shard_of(id, 7), shard_of(other_id, 7));
+    assert_eq!(shard_of(id, 1), 0);
+    // Pinned, as routing depends on the shards never changing.
+    assert_eq!([shard_of(0, 10), shard_of(1 << 22, 10), shard_of(0x1234_5678_9ABC, 1_000)], [5, 1, 384]);
+}
+
+#[test]
+fn test_micros_layout_round_trip() {
+    use crate::{MicrosSnowflake, SnowflakeDecomposed};
+    // The clock reports microseconds.
+    let clock = MockClock::new(1_700_000_000_000_000);
+    let epoch = 1_600_000_000_000_000;
+    let mut state = MicrosSnowflake::with_clock(epoch, 0x2A5, clock.clone()).unwrap();
+    let ids: Vec<i64> = (0..16).map(|_| state.generate_id()).collect();
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    for (sequence, &id) in ids.iter().enumerate() {
+        let expected = SnowflakeDecomposed { timestamp: 1_700_000_000_000_000, worker_id: 0x2A5, sequence: sequence as u16 };
+        assert_eq!(state.decompose(id), expected);
+        assert_eq!(state.try_decompose(id), Ok(expected));
+    }
+    assert!(matches!(state.try_generate_id(), Err(SnowflakeError::SequenceExhausted)));
+    clock.advance(1);
+    let next = state.generate_id();
+    assert_eq!(state.decompose(next).timestamp, 1_700_000_000_000_001);
+    assert_eq!(next >> 14, 1_700_000_000_000_001 - epoch);
+
+    let mut state = MicrosSnowflake::new_micros(1_600_000_000_000_000, 312).unwrap();
+    let id = state.generate_id();
+    let now_micros = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_micros() as i64;
+    assert!((now_micros - state.decompose(id).timestamp).abs() < 1_000_000);
+}
+
+#[test]
+fn test_system_time_error_is_returned_not_panicked() {
+    use crate::{Result, SystemClock};
+    let _serial = serial();
+    // A system time before the Unix epoch cannot be simulated, so only check the error flow.
+    let clock: Result<SystemClock> = SystemClock::new();
+    assert!(clock.is_ok());
+    let state: Result<DefaultSnowflake> = DefaultSnowflake::new(0, 313);
+    assert!(state.is_ok());
+    assert_eq!(SnowflakeError::SystemTimeError.to_string(), "System time is before the Unix epoch");
+}
+
+fn collect_ids<T: crate::SnowflakeSource>(source: &T, n: usize) -> Vec<i64> {
+    (0..n).map(|_| source.generate_id()).collect()
+}
+
+#[test]
+fn test_snowflake_source_generic_over_sync_generators() {
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let single = STSG::with_clock(0, 1, clock.clone()).unwrap();
+    let ids = collect_ids(&single, 100);
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    // The same layout, clock and worker ID give the same IDs whatever the generator. One at
+    // a time, as dedup-check rejects two live generators on worker 1.
+    drop(single);
+    let multi = MTSG::with_clock(0, 1, clock.clone()).unwrap();
+    assert_eq!(collect_ids(&multi, 100), ids);
+    drop(multi);
+    let atomic = MTAtG::with_clock(0, 1, clock).unwrap();
+    assert_eq!(collect_ids(&atomic, 100), ids);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_async_snowflake_source() {
+    use crate::AsyncSnowflakeSource;
+    let _serial = serial_async().await;
+    async fn collect_ids_async<T: AsyncSnowflakeSource>(source: &T, n: usize) -> Vec<i64> {
+        let mut ids = Vec::with_capacity(n);
+        for _ in 0..n {
+            ids.push(source.generate_id().await);
+        }
+        ids
+    }
+    let generator = MTAG::with_clock(0, 1, MockClock::new(10_000)).unwrap();
+    let task = tokio::spawn(async move { collect_ids_async(&generator, 100).await });
+    let ids = task.await.unwrap();
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn test_generate_id_as_other_workers() {
+    let _serial = serial();
+    let clock = MockClock::new(10_000);
+    let generator = MTSG::with_clock(0, 1, clock.clone()).unwrap();
+    let own = generator.generate_id();
+    let as_a = generator.generate_id_as(7).unwrap();
+    let as_b = generator.generate_id_as(900).unwrap();
+    let as_a_again = generator.generate_id_as(7).unwrap();
+    let ids = [own, as_a, as_b, as_a_again];
+    let decomposed: Vec<_> = ids.iter().map(|&id| generator.decompose(id)).collect();
+    assert!(decomposed.iter().all(|d| d.timestamp == 10_000));
+    assert_eq!(decomposed.iter().map(|d| d.worker_id).collect::<Vec<_>>(), [1, 7, 900, 7]);
+    // The sequence is shared, so the same override twice in a millisecond does not collide.
+    assert_eq!(decomposed.iter().map(|d| d.sequence).collect::<Vec<_>>(), [0, 1, 2, 3]);
+    assert_ne!(as_a, as_a_again);
+    assert_eq!(generator.decompose(generator.generate_id()).worker_id, 1);
+    assert!(matches!(generator.generate_id_as(1_024), Err(SnowflakeError::WorkerIdOutOfRange)));
+}
+
+#[test]
+fn test_saturating_i64_at_the_boundary() {
+    use crate::common::saturating_i64;
+    use std::time::Duration;
+    let max = Duration::from_millis(i64::MAX as u64);
+    assert_eq!(saturating_i64(max.as_millis()), i64::MAX);
+    assert_eq!(saturating_i64((max - Duration::from_millis(1)).as_millis()), i64::MAX - 1);
+    assert_eq!(saturating_i64((max + Duration::from_millis(1)).as_millis()), i64::MAX);
+    assert_eq!(saturating_i64(Duration::MAX.as_micros()), i64::MAX);
+    assert_eq!(saturating_i64(Duration::ZERO.as_millis()), 0);
+}
+
+#[test]
+fn test_timestamp_of_matches_decompose() {
+    use crate::{decompose_snowflake, timestamp_of};
+    let epoch = 1_288_834_974_657;
+    for id in [0, 1, 4_194_304, 175_928_847_299_117_063, i64::MAX] {
+        assert_eq!(timestamp_of(id, epoch).unwrap(), decompose_snowflake(id, epoch).unwrap().timestamp);
+    }
+    assert!(matches!(timestamp_of(-1, epoch), Err(SnowflakeError::NegativeId)));
+    assert!(matches!(timestamp_of(i64::MAX, i64::MAX), Err(SnowflakeError::TimestampOverflow)));
+}
@@ -0,0 +1,61 @@
+//! A generator with one `SnowflakeState` per thread, so IDs are generated without any locking.
+//!
+//! Worker IDs are allocated on each thread's first call to [`generate_id`]: the first
+//! thread gets `base_worker_id`, the next `base_worker_id + 1`, and so on. IDs are never
+//! reused, even after a thread exits, so at most `1024 - base_worker_id` threads can
+//! generate IDs over the lifetime of the process.
+
+use crate::common::{DefaultSnowflake, Result, SnowflakeError, MAX_WORKER_ID};
+use std::cell::RefCell;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+static CONFIG: OnceLock<(i64, u16)> = OnceLock::new();
+static NEXT_WORKER: AtomicU32 = AtomicU32::new(0);
+
+thread_local! {
+    static STATE: RefCell<Option<DefaultSnowflake>> = const { RefCell::new(None) };
+}
+
+/// Set the epoch and the first worker ID used by the thread-local generators.
+///
+/// # Arguments
+/// * `epoch` - The custom epoch timestamp in milliseconds.
+/// * `base_worker_id` - The worker ID given to the first thread (0-1023).
+/// # Errors
+/// Returns `SnowflakeError::WorkerIdOutOfRange` if the base_worker_id is out of range.
+/// Returns `SnowflakeError::AlreadyConfigured` if `configure` was already called.
+pub fn configure(epoch: i64, base_worker_id: u16) -> Result<()> {
+    if base_worker_id > MAX_WORKER_ID {
+        return Err(SnowflakeError::WorkerIdOutOfRange);
+    }
+    CONFIG
+        .set((epoch, base_worker_id))
+        .map_err(|_| SnowflakeError::AlreadyConfigured)
+}
+
+/// Generate a new Snowflake ID with the current thread's generator.
+///
+/// # Errors
+/// Returns `SnowflakeError::NotConfigured` if [`configure`] has not been called.
+/// Returns `SnowflakeError::WorkerIdsExhausted` if this thread is the first to use the
+/// generator after all worker IDs have been handed out.
+/// Returns the errors of `SnowflakeState::new` if the thread's state cannot be created.
+pub fn generate_id() -> Result<i64> {
+    STATE.with(|cell| {
+        let mut state = cell.borrow_mut();
+        if state.is_none() {
+            *state = Some(new_thread_state()?);
+        }
+        Ok(state.as_mut().expect("state initialized above").generate_id())
+    })
+}
+
+fn new_thread_state() -> Result<DefaultSnowflake> {
+    let (epoch, base_worker_id) = *CONFIG.get().ok_or(SnowflakeError::NotConfigured)?;
+    let worker_id = base_worker_id as u32 + NEXT_WORKER.fetch_add(1, Ordering::Relaxed);
+    if worker_id > MAX_WORKER_ID as u32 {
+        return Err(SnowflakeError::WorkerIdsExhausted);
+    }
+    DefaultSnowflake::new(epoch, worker_id as u16)
+}
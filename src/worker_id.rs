@@ -0,0 +1,88 @@
+use crate::common::{Result, SnowflakeError, MAX_WORKER_ID};
+
+/// Hash arbitrary bytes into the worker ID range (0-1023) using 64-bit FNV-1a.
+///
+/// FNV-1a is used instead of `DefaultHasher` because its output is stable across
+/// Rust versions, so a given host always maps to the same worker ID.
+pub fn worker_id_from_bytes(bytes: &[u8]) -> u16 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for &b in bytes {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    (hash % 1024) as u16
+}
+
+/// Derive a worker ID (0-1023) from the machine hostname.
+///
+/// Distinct hosts can map to the same worker ID. With `n` hosts the probability of at
+/// least one collision is roughly `1 - e^(-n(n-1)/2048)`: about 4% for 10 hosts and
+/// 50% for 38 hosts. Assign worker IDs explicitly when that is not acceptable.
+///
+/// # Errors
+/// Returns `SnowflakeError::MachineIdUnavailable` if the hostname is empty.
+pub fn worker_id_from_hostname() -> Result<u16> {
+    let hostname = gethostname::gethostname();
+    if hostname.is_empty() {
+        return Err(SnowflakeError::MachineIdUnavailable);
+    }
+    Ok(worker_id_from_bytes(hostname.as_encoded_bytes()))
+}
+
+/// Read the worker ID (0-1023) from an environment variable, e.g. one injected into a
+/// container by the orchestrator.
+///
+/// Leading and trailing whitespace is ignored.
+///
+/// # Errors
+/// Returns `SnowflakeError::EnvVarMissing` if the variable is not set.
+/// Returns `SnowflakeError::EnvVarInvalid` if the value is not a decimal u16.
+/// Returns `SnowflakeError::WorkerIdOutOfRange` if the value is above 1023.
+pub fn worker_id_from_env(var: &str) -> Result<u16> {
+    let value = match std::env::var(var) {
+        Ok(value) => value,
+        Err(std::env::VarError::NotPresent) => return Err(SnowflakeError::EnvVarMissing),
+        Err(std::env::VarError::NotUnicode(_)) => return Err(SnowflakeError::EnvVarInvalid),
+    };
+    let worker_id: u16 = value.trim().parse().map_err(|_| SnowflakeError::EnvVarInvalid)?;
+    if worker_id > MAX_WORKER_ID {
+        return Err(SnowflakeError::WorkerIdOutOfRange);
+    }
+    Ok(worker_id)
+}
+
+/// Read the worker ID (0-1023) from the ordinal ending the name of a Kubernetes
+/// StatefulSet pod, e.g. 5 for `web-5`.
+///
+/// The ordinal is stable across restarts of the pod, so unlike a hashed worker ID it
+/// cannot collide as long as the StatefulSet has at most 1024 replicas.
+///
+/// # Errors
+/// Returns `SnowflakeError::MissingPodOrdinal` if the name does not end in `-` followed by digits.
+/// Returns `SnowflakeError::WorkerIdOutOfRange` if the ordinal is above 1023.
+pub fn worker_id_from_pod_name(name: &str) -> Result<u16> {
+    let ordinal = match name.rsplit_once('-') {
+        Some((_, ordinal)) if !ordinal.is_empty() && ordinal.bytes().all(|b| b.is_ascii_digit()) => ordinal,
+        _ => return Err(SnowflakeError::MissingPodOrdinal),
+    };
+    // Only digits are left, so a failed parse means the ordinal overflows a u16.
+    let worker_id: u16 = ordinal.parse().map_err(|_| SnowflakeError::WorkerIdOutOfRange)?;
+    if worker_id > MAX_WORKER_ID {
+        return Err(SnowflakeError::WorkerIdOutOfRange);
+    }
+    Ok(worker_id)
+}
+
+/// Derive a worker ID (0-1023) from the MAC address of the first non-loopback interface.
+///
+/// The same collision probability as [`worker_id_from_hostname`] applies.
+///
+/// # Errors
+/// Returns `SnowflakeError::MachineIdUnavailable` if no MAC address can be read.
+#[cfg(feature = "machine-id")]
+pub fn worker_id_from_mac() -> Result<u16> {
+    match mac_address::get_mac_address() {
+        Ok(Some(mac)) => Ok(worker_id_from_bytes(&mac.bytes())),
+        _ => Err(SnowflakeError::MachineIdUnavailable),
+    }
+}